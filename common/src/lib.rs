@@ -0,0 +1,91 @@
+// the bits every day's crate would otherwise have its own copy of
+
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub enum JsonArgsError {
+    MissingPath,
+    Read(String, std::io::Error),
+    Json(serde_json::Error),
+}
+
+#[cfg(feature = "serde")]
+impl std::fmt::Display for JsonArgsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JsonArgsError::MissingPath => {
+                write!(f, "--from-json needs the path of a model to load")
+            }
+            JsonArgsError::Read(path, e) => write!(f, "couldn't read {}: {}", path, e),
+            JsonArgsError::Json(e) => write!(f, "that isn't a model for this day: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for JsonArgsError {}
+
+// `--dump-json` prints the parsed model and stops (so Ok(None)), and `--from-json <file>`
+// solves a model loaded from disk instead of the puzzle input
+#[cfg(feature = "serde")]
+pub fn apply_json_args<T: serde::Serialize + serde::de::DeserializeOwned>(
+    input: T,
+) -> Result<Option<T>, JsonArgsError> {
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.iter().any(|a| a == "--dump-json") {
+        let json = serde_json::to_string_pretty(&input).map_err(JsonArgsError::Json)?;
+        println!("{}", json);
+        return Ok(None);
+    }
+
+    match args.iter().position(|a| a == "--from-json") {
+        Some(index) => {
+            let path = args.get(index + 1).ok_or(JsonArgsError::MissingPath)?;
+            let json =
+                std::fs::read_to_string(path).map_err(|e| JsonArgsError::Read(path.clone(), e))?;
+            serde_json::from_str(&json)
+                .map(Some)
+                .map_err(JsonArgsError::Json)
+        }
+        None => Ok(Some(input)),
+    }
+}
+
+// what every day's try_parse_input fails with, saying which line was wrong and why
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError(pub String);
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// JSON map keys have to be strings, so maps keyed by anything richer get written out as a list of pairs
+#[cfg(feature = "serde")]
+pub mod map_as_pairs {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    pub fn serialize<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(map)
+    }
+
+    pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<(K, V)>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
@@ -0,0 +1,318 @@
+use std::io::BufRead;
+
+pub use common::ParseError;
+
+// one column per source, each sorted
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "Columns"))]
+pub struct Lists {
+    columns: Vec<Vec<i64>>,
+}
+
+// lists loaded from json, which haven't been through read_lists to make sure of all that
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct Columns {
+    columns: Vec<Vec<i64>>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<Columns> for Lists {
+    type Error = ParseError;
+
+    fn try_from(Columns { columns }: Columns) -> Result<Self, ParseError> {
+        if columns.len() < 2 {
+            return Err(ParseError("expected at least two lists".to_string()));
+        }
+        if columns.iter().any(|c| c.len() != columns[0].len()) {
+            return Err(ParseError("the lists should all be the same length".to_string()));
+        }
+        if columns.iter().any(|c| !c.is_sorted()) {
+            return Err(ParseError("the lists should be sorted".to_string()));
+        }
+
+        Ok(Lists { columns })
+    }
+}
+
+// reads a line at a time, so the lists don't have to be in memory twice
+pub fn read_lists(reader: impl BufRead) -> Result<Lists, ParseError> {
+    let mut columns: Vec<Vec<i64>> = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| ParseError(format!("line {}: {}", number + 1, e)))?;
+        if line.is_empty() {
+            continue;
+        }
+
+        let ids = line
+            .split_ascii_whitespace()
+            .map(|id| id.parse::<i64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| ParseError(format!("line {}: location ids should be numbers", number + 1)))?;
+        if columns.is_empty() {
+            if ids.len() < 2 {
+                return Err(ParseError(format!("line {}: expected at least two location ids", number + 1)));
+            }
+            columns = vec![Vec::new(); ids.len()];
+        }
+        if ids.len() != columns.len() {
+            return Err(ParseError(format!(
+                "line {}: expected {} location ids, found {}",
+                number + 1,
+                columns.len(),
+                ids.len()
+            )));
+        }
+
+        for (column, id) in columns.iter_mut().zip(ids) {
+            column.push(id);
+        }
+    }
+
+    if columns.is_empty() {
+        return Err(ParseError("there are no location ids".to_string()));
+    }
+    for column in &mut columns {
+        column.sort();
+    }
+
+    Ok(Lists { columns })
+}
+
+pub fn try_parse_input(input: &str) -> Result<Lists, ParseError> {
+    read_lists(input.as_bytes())
+}
+
+pub fn parse_input(input: &str) -> Lists {
+    try_parse_input(input).unwrap()
+}
+
+impl Lists {
+    pub fn columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn column(&self, index: usize) -> Option<&[i64]> {
+        self.columns.get(index).map(Vec::as_slice)
+    }
+
+    // any two of the lists, to compare against each other
+    pub fn pair(&self, left: usize, right: usize) -> Option<Pair<'_>> {
+        Some(Pair {
+            left: self.column(left)?,
+            right: self.column(right)?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pair<'a> {
+    left: &'a [i64],
+    right: &'a [i64],
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Discrepancy {
+    // where the pair sits in the sorted lists
+    pub rank: usize,
+    pub left: i64,
+    pub right: i64,
+    pub distance: u64,
+}
+
+// how much one id adds to the similarity score
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Contribution {
+    pub id: i64,
+    pub left_count: usize,
+    pub right_count: usize,
+    // an i64 id times two counts can be too big for an i64
+    pub score: i128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reconciliation {
+    // biggest first
+    pub largest_discrepancies: Vec<Discrepancy>,
+    pub only_in_left: Vec<i64>,
+    pub only_in_right: Vec<i64>,
+    // biggest first, and only the ids that are in both lists
+    pub contributions: Vec<Contribution>,
+}
+
+// each distinct id in a sorted list, with how many times it appears
+fn runs(sorted: &[i64]) -> Vec<(i64, usize)> {
+    sorted
+        .chunk_by(|a, b| a == b)
+        .map(|run| (run[0], run.len()))
+        .collect()
+}
+
+impl Pair<'_> {
+    // any one difference fits in a u64, but a few of them added up needn't
+    pub fn distance(&self) -> u128 {
+        self.left.iter()
+            .zip(self.right.iter())
+            .map(|(l, r)| l.abs_diff(*r) as u128)
+            .sum()
+    }
+
+    pub fn similarity(&self) -> i128 {
+        self.contributions().iter().map(|c| c.score).sum()
+    }
+
+    // both lists are sorted, so walking them side by side lines up the matching ids
+    fn contributions(&self) -> Vec<Contribution> {
+        let left = runs(self.left);
+        let right = runs(self.right);
+        let mut contributions = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < left.len() && j < right.len() {
+            match left[i].0.cmp(&right[j].0) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    let (id, left_count) = left[i];
+                    let right_count = right[j].1;
+                    contributions.push(Contribution {
+                        id,
+                        left_count,
+                        right_count,
+                        score: id as i128 * left_count as i128 * right_count as i128,
+                    });
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+
+        contributions
+    }
+
+    pub fn reconcile(&self, discrepancies: usize) -> Reconciliation {
+        let mut largest_discrepancies = self.left.iter()
+            .zip(self.right.iter())
+            .enumerate()
+            .map(|(rank, (l, r))| Discrepancy { rank, left: *l, right: *r, distance: l.abs_diff(*r) })
+            .collect::<Vec<_>>();
+        largest_discrepancies.sort_by(|a, b| b.distance.cmp(&a.distance).then(a.rank.cmp(&b.rank)));
+        largest_discrepancies.truncate(discrepancies);
+
+        let missing_from = |list: &[i64], other: &[i64]| {
+            runs(list)
+                .into_iter()
+                .map(|(id, _)| id)
+                .filter(|id| other.binary_search(id).is_err())
+                .collect::<Vec<_>>()
+        };
+
+        let mut contributions = self.contributions();
+        contributions.sort_by(|a, b| b.score.cmp(&a.score).then(a.id.cmp(&b.id)));
+
+        Reconciliation {
+            largest_discrepancies,
+            only_in_left: missing_from(self.left, self.right),
+            only_in_right: missing_from(self.right, self.left),
+            contributions,
+        }
+    }
+}
+
+impl std::fmt::Display for Reconciliation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "largest discrepancies:")?;
+        for d in &self.largest_discrepancies {
+            writeln!(f, "  #{}: {} vs {} ({} apart)", d.rank + 1, d.left, d.right, d.distance)?;
+        }
+        writeln!(f, "only in the left list: {:?}", self.only_in_left)?;
+        writeln!(f, "only in the right list: {:?}", self.only_in_right)?;
+        writeln!(f, "similarity contributions:")?;
+        for c in &self.contributions {
+            writeln!(f, "  {}: {} x {} -> {}", c.id, c.left_count, c.right_count, c.score)?;
+        }
+
+        Ok(())
+    }
+}
+
+// parsing makes sure there are always at least two lists
+pub fn part_1(input: &Lists) -> u128 {
+    input.pair(0, 1).unwrap().distance()
+}
+
+pub fn part_2(input: &Lists) -> i128 {
+    input.pair(0, 1).unwrap().similarity()
+}
+
+#[test]
+pub fn test() {
+    let input = r#"3   4
+4   3
+2   5
+1   3
+3   9
+3   3
+"#;
+
+    let input = parse_input(input);
+    assert_eq!(part_1(&input), 11);
+    assert_eq!(part_2(&input), 31);
+}
+
+#[test]
+pub fn test_reconcile() {
+    let input = r#"3   4   3
+4   3   4
+2   5   2
+1   3   1
+3   9   3
+3   3   -3000000000
+"#;
+
+    let input = parse_input(input);
+    assert_eq!(input.columns(), 3);
+    assert!(input.pair(0, 3).is_none());
+    assert_eq!(input.pair(0, 2).unwrap().distance(), 3000000003);
+    // the score comes out the same whichever way round the lists are
+    assert_eq!(input.pair(1, 0).unwrap().similarity(), 31);
+
+    let report = input.pair(0, 1).unwrap().reconcile(2);
+    assert_eq!(report.largest_discrepancies, vec![
+        Discrepancy { rank: 5, left: 4, right: 9, distance: 5 },
+        Discrepancy { rank: 0, left: 1, right: 3, distance: 2 },
+    ]);
+    assert_eq!(report.only_in_left, vec![1, 2]);
+    assert_eq!(report.only_in_right, vec![5, 9]);
+    // 3 is in the left list three times and the right list three times
+    assert_eq!(report.contributions, vec![
+        Contribution { id: 3, left_count: 3, right_count: 3, score: 27 },
+        Contribution { id: 4, left_count: 1, right_count: 1, score: 4 },
+    ]);
+    assert_eq!(report.contributions.iter().map(|c| c.score).sum::<i128>(), part_2(&input));
+
+    // far more than an i64 holds
+    let big = parse_input("9000000000000000000 9000000000000000000\n9000000000000000000 9000000000000000000\n");
+    assert_eq!(part_2(&big), 36_000_000_000_000_000_000);
+    let far = parse_input("-9000000000000000000 9000000000000000000\n-9000000000000000000 9000000000000000000\n");
+    assert_eq!(part_1(&far), 36_000_000_000_000_000_000);
+
+    assert!(try_parse_input("1 2\n3\n").is_err());
+    assert!(try_parse_input("1\n").is_err());
+    assert!(try_parse_input("").is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+pub fn test_json() {
+    let input = parse_input("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n");
+    let json = serde_json::to_string(&input).unwrap();
+    let reloaded: Lists = serde_json::from_str(&json).unwrap();
+    assert_eq!(reloaded, input);
+    assert_eq!(part_2(&reloaded), 31);
+
+    // anything read_lists wouldn't have produced is turned away
+    assert!(serde_json::from_str::<Lists>(r#"{"columns":[[1,2]]}"#).is_err());
+    assert!(serde_json::from_str::<Lists>(r#"{"columns":[[1,2],[3]]}"#).is_err());
+    assert!(serde_json::from_str::<Lists>(r#"{"columns":[[2,1],[3,4]]}"#).is_err());
+}
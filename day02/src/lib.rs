@@ -0,0 +1,525 @@
+use std::cmp::Reverse;
+
+pub use common::ParseError;
+
+pub fn try_parse_input(input: &str) -> Result<Vec<Vec<i64>>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(number, l)| {
+            l.split_ascii_whitespace()
+                .map(|n| {
+                    n.parse()
+                        .map_err(|_| ParseError(format!("line {}: {:?} isn't a level", number + 1, n)))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub fn parse_input(input: &str) -> Vec<Vec<i64>> {
+    try_parse_input(input).unwrap()
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Directions {
+    Increasing,
+    Decreasing,
+    Either,
+}
+
+// what counts as a safe report - the default is the puzzle's, with nothing tolerated
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SafetyPolicy {
+    pub min_step: i64,
+    pub max_step: i64,
+    pub directions: Directions,
+    // a step of zero is fine whichever way the report is going
+    pub allow_zero_steps: bool,
+    // how many levels can be taken out to make a report safe
+    pub tolerance: usize,
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        Self {
+            min_step: 1,
+            max_step: 3,
+            directions: Directions::Either,
+            allow_zero_steps: false,
+            tolerance: 0,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Rule {
+    ZeroStep,
+    WrongDirection { step: i64 },
+    StepTooSmall { step: i64, min: i64 },
+    StepTooLarge { step: i64, max: i64 },
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rule::ZeroStep => write!(f, "the level doesn't change"),
+            Rule::WrongDirection { step } => write!(f, "a step of {} goes the wrong way", step),
+            Rule::StepTooSmall { step, min } => write!(f, "a step of {} is less than {}", step, min),
+            Rule::StepTooLarge { step, max } => write!(f, "a step of {} is more than {}", step, max),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    // the indices of the two levels either side of the bad step
+    pub window: (usize, usize),
+    pub levels: (i64, i64),
+    pub rule: Rule,
+}
+
+// why a report was turned down, and the least that would have to change for it not to be
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Explanation {
+    pub violation: Violation,
+    pub fix: Vec<usize>,
+}
+
+impl std::fmt::Display for Explanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Violation { window, levels, rule } = &self.violation;
+        write!(
+            f,
+            "levels {} and {} ({} then {}): {}; removing level{} {:?} would fix it",
+            window.0,
+            window.1,
+            levels.0,
+            levels.1,
+            rule,
+            if self.fix.len() == 1 { "" } else { "s" },
+            self.fix
+        )
+    }
+}
+
+impl SafetyPolicy {
+    pub const fn with_tolerance(self, tolerance: usize) -> Self {
+        Self { tolerance, ..self }
+    }
+
+    fn directions(&self) -> &'static [i64] {
+        match self.directions {
+            Directions::Increasing => &[1],
+            Directions::Decreasing => &[-1],
+            Directions::Either => &[1, -1],
+        }
+    }
+
+    // going from one level to the next, in the given direction (1 up, -1 down)
+    fn check(&self, from: i64, to: i64, direction: i64) -> Result<(), Rule> {
+        let step = to.saturating_sub(from);
+        if step == 0 {
+            return if self.allow_zero_steps { Ok(()) } else { Err(Rule::ZeroStep) };
+        }
+
+        let step_in_direction = step.saturating_mul(direction);
+        if step_in_direction < 0 {
+            Err(Rule::WrongDirection { step })
+        } else if step_in_direction < self.min_step {
+            Err(Rule::StepTooSmall { step, min: self.min_step })
+        } else if step_in_direction > self.max_step {
+            Err(Rule::StepTooLarge { step, max: self.max_step })
+        } else {
+            Ok(())
+        }
+    }
+
+    // the first bad step in the report as it stands - a report that can go either way
+    // is held to whichever way its first change goes
+    pub fn first_violation(&self, report: &[i64]) -> Option<Violation> {
+        let direction = match self.directions {
+            Directions::Increasing => 1,
+            Directions::Decreasing => -1,
+            Directions::Either => report
+                .windows(2)
+                .map(|w| w[1].saturating_sub(w[0]).signum())
+                .find(|d| *d != 0)
+                .unwrap_or(1),
+        };
+
+        report.windows(2).enumerate().find_map(|(i, w)| {
+            self.check(w[0], w[1], direction).err().map(|rule| Violation {
+                window: (i, i + 1),
+                levels: (w[0], w[1]),
+                rule,
+            })
+        })
+    }
+
+    // the fewest levels to take out to make the report safe, if that's within the tolerance
+    pub fn levels_to_remove(&self, report: &[i64]) -> Option<Vec<usize>> {
+        self.fewest_removals(report, self.tolerance)
+    }
+
+    pub fn is_safe(&self, report: &[i64]) -> bool {
+        self.levels_to_remove(report).is_some()
+    }
+
+    // None if the report is safe
+    pub fn explain(&self, report: &[i64]) -> Option<Explanation> {
+        if self.is_safe(report) {
+            return None;
+        }
+
+        Some(Explanation {
+            violation: self.first_violation(report)?,
+            // a single level is always safe, so there's always some fix
+            fix: self.fewest_removals(report, report.len())?,
+        })
+    }
+
+    // keeping level i straight after level j means dropping everything between them, so
+    // with at most k dropped only the k + 1 levels before i can come before it - which
+    // keeps this to n * k steps rather than the n * n of trying every subsequence
+    fn fewest_removals(&self, report: &[i64], k: usize) -> Option<Vec<usize>> {
+        if report.is_empty() {
+            return Some(Vec::new());
+        }
+
+        // with no limit on how many go, there's no window to keep the search to
+        let unbounded = k >= report.len() - 1;
+        self.directions()
+            .iter()
+            .filter_map(|direction| {
+                if unbounded {
+                    Some(self.longest_kept_in_direction(report, *direction))
+                } else {
+                    self.fewest_removals_in_direction(report, k, *direction)
+                }
+            })
+            .min_by_key(|removed| removed.len())
+    }
+
+    // keeping as many levels as possible, when any number can go: each level follows on from
+    // the longest run so far that ends a step away from it, found by looking the runs up by the
+    // value they end on - so n log n, however long the report. ties go to the earliest levels,
+    // the same as fewest_removals_in_direction
+    fn longest_kept_in_direction(&self, report: &[i64], direction: i64) -> Vec<usize> {
+        if report.is_empty() {
+            return Vec::new();
+        }
+        let mut values = report.to_vec();
+        values.sort();
+        values.dedup();
+        let size = values.len();
+        // how many values are below, or at most, the given one
+        let below = |value: i128| values.partition_point(|v| (*v as i128) < value);
+        let up_to = |value: i128| values.partition_point(|v| (*v as i128) <= value);
+
+        // a max segment tree over the values: the longest run ending on each one, and the
+        // earliest level it ends at
+        let none = (0, Reverse(usize::MAX));
+        let mut tree = vec![none; 2 * size];
+        let longest = |tree: &[(usize, Reverse<usize>)], from: usize, to: usize| {
+            let (mut from, mut to) = (from + size, to + size);
+            let mut best = none;
+            while from < to {
+                if from % 2 == 1 {
+                    best = best.max(tree[from]);
+                    from += 1;
+                }
+                if to % 2 == 1 {
+                    to -= 1;
+                    best = best.max(tree[to]);
+                }
+                from /= 2;
+                to /= 2;
+            }
+            best
+        };
+
+        // a step checks out if it goes the right way by between these two, and `check`
+        // saturates, so the largest possible max_step lets anything through
+        let smallest = self.min_step.max(1) as i128;
+        let largest = if self.max_step == i64::MAX { i128::MAX } else { self.max_step as i128 };
+
+        let mut previous = vec![None; report.len()];
+        let mut end = none;
+        for (i, level) in report.iter().enumerate() {
+            let level = *level as i128;
+            // the values the level before this one could have
+            let (from, to) = if direction == 1 {
+                (level.saturating_sub(largest), level - smallest)
+            } else {
+                (level + smallest, level.saturating_add(largest))
+            };
+            let mut best = if from <= to { longest(&tree, below(from), up_to(to)) } else { none };
+            if self.allow_zero_steps {
+                best = best.max(longest(&tree, below(level), up_to(level)));
+            }
+            if best.0 > 0 {
+                previous[i] = Some(best.1.0);
+            }
+
+            let run = (best.0 + 1, Reverse(i));
+            end = end.max(run);
+            let mut node = below(level) + size;
+            if run > tree[node] {
+                tree[node] = run;
+                while node > 1 {
+                    node /= 2;
+                    tree[node] = tree[2 * node].max(tree[2 * node + 1]);
+                }
+            }
+        }
+
+        let mut kept = vec![false; report.len()];
+        let mut current = Some(end.1.0);
+        while let Some(i) = current {
+            kept[i] = true;
+            current = previous[i];
+        }
+
+        (0..report.len()).filter(|&i| !kept[i]).collect()
+    }
+
+    fn fewest_removals_in_direction(&self, report: &[i64], k: usize, direction: i64) -> Option<Vec<usize>> {
+        // removed[i] is the fewest levels dropped up to i with i kept, and previous[i] the level kept before it
+        let mut removed = vec![usize::MAX; report.len()];
+        let mut previous = vec![None; report.len()];
+        for i in 0..report.len() {
+            if i <= k {
+                removed[i] = i;
+            }
+            for j in i.saturating_sub(k.saturating_add(1))..i {
+                if removed[j] == usize::MAX || self.check(report[j], report[i], direction).is_err() {
+                    continue;
+                }
+                let dropped = removed[j] + (i - j - 1);
+                if dropped < removed[i] {
+                    removed[i] = dropped;
+                    previous[i] = Some(j);
+                }
+            }
+        }
+
+        let (last, _) = (0..report.len())
+            .filter(|&i| removed[i] != usize::MAX)
+            .map(|i| (i, removed[i] + (report.len() - 1 - i)))
+            .filter(|&(_, dropped)| dropped <= k)
+            .min_by_key(|&(_, dropped)| dropped)?;
+
+        let mut kept = vec![false; report.len()];
+        let mut current = Some(last);
+        while let Some(i) = current {
+            kept[i] = true;
+            current = previous[i];
+        }
+
+        Some((0..report.len()).filter(|&i| !kept[i]).collect())
+    }
+}
+
+pub fn is_valid(report: &[i64]) -> bool {
+    SafetyPolicy::default().first_violation(report).is_none()
+}
+
+pub fn levels_to_remove(report: &[i64], k: usize) -> Option<Vec<usize>> {
+    SafetyPolicy::default().with_tolerance(k).levels_to_remove(report)
+}
+
+pub fn is_valid_with_tolerance(report: &[i64]) -> bool {
+    levels_to_remove(report, 1).is_some()
+}
+
+// the original - try taking out each level in turn
+pub fn is_valid_with_tolerance_brute_force(report: &[i64]) -> bool {
+    (0..report.len()).any(|n| {
+        let mut modified_list = report.to_vec();
+        modified_list.remove(n);
+        is_valid(&modified_list)
+    })
+}
+
+// every unsafe report, by its index
+pub fn explain_all(reports: &[Vec<i64>], policy: &SafetyPolicy) -> Vec<(usize, Explanation)> {
+    reports
+        .iter()
+        .enumerate()
+        .filter_map(|(i, r)| Some((i, policy.explain(r)?)))
+        .collect()
+}
+
+// safe as they are, whatever the policy tolerates
+pub fn part_1(reports: &[Vec<i64>], policy: &SafetyPolicy) -> usize {
+    let policy = policy.with_tolerance(0);
+    reports.iter().filter(|r| policy.is_safe(r)).count()
+}
+
+pub fn part_2(reports: &[Vec<i64>], policy: &SafetyPolicy) -> usize {
+    reports.iter().filter(|r| policy.is_safe(r)).count()
+}
+
+pub fn part_2_brute_force(reports: &[Vec<i64>]) -> usize {
+    reports
+        .iter()
+        .filter(|r| is_valid_with_tolerance_brute_force(r))
+        .count()
+}
+
+#[test]
+pub fn test() {
+    let input = r#"7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9
+"#;
+
+    let input = parse_input(input);
+    let policy = SafetyPolicy::default().with_tolerance(1);
+    assert_eq!(part_1(&input, &policy), 2);
+    assert_eq!(part_2(&input, &policy), 4);
+}
+
+#[test]
+pub fn test_levels_to_remove() {
+    assert_eq!(levels_to_remove(&[7, 6, 4, 2, 1], 0), Some(vec![]));
+    // taking out the 3 would do just as well
+    assert_eq!(levels_to_remove(&[1, 3, 2, 4, 5], 1), Some(vec![2]));
+    assert_eq!(levels_to_remove(&[8, 6, 4, 4, 1], 1), Some(vec![3]));
+    assert_eq!(levels_to_remove(&[1, 2, 7, 8, 9], 1), None);
+    // 7 and 8 both have to go, as a pair
+    assert_eq!(levels_to_remove(&[1, 2, 7, 8, 3, 4], 2), Some(vec![2, 3]));
+    assert_eq!(levels_to_remove(&[1, 2, 7, 8, 3, 4], 1), None);
+    assert_eq!(levels_to_remove(&[5], 0), Some(vec![]));
+    assert_eq!(levels_to_remove(&[], 0), Some(vec![]));
+    assert_eq!(levels_to_remove(&[i64::MIN, 1, 2], usize::MAX), Some(vec![0]));
+
+    // a long report with a bad level every hundred
+    let report = (0..10_000)
+        .map(|i| if i % 100 == 50 { -1 } else { i })
+        .collect::<Vec<_>>();
+    let removed = levels_to_remove(&report, 100).unwrap();
+    assert_eq!(removed, (0..100).map(|i| i * 100 + 50).collect::<Vec<_>>());
+    assert_eq!(levels_to_remove(&report, 99), None);
+
+    // agrees with trying every level in turn, for every short report - bar the empty
+    // one, which the brute force turns down for having no level to take out
+    for length in 1..=5u32 {
+        for mut n in 0..6i64.pow(length) {
+            let report = (0..length)
+                .map(|_| {
+                    let level = n % 6;
+                    n /= 6;
+                    level
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                is_valid_with_tolerance(&report),
+                is_valid_with_tolerance_brute_force(&report),
+                "{:?}",
+                report
+            );
+        }
+    }
+}
+
+#[test]
+pub fn test_policy() {
+    let reports = parse_input(
+        "7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9
+",
+    );
+
+    let increasing = SafetyPolicy {
+        directions: Directions::Increasing,
+        ..SafetyPolicy::default()
+    };
+    assert_eq!(part_1(&reports, &increasing), 1);
+    let wide_steps = SafetyPolicy {
+        max_step: 5,
+        ..SafetyPolicy::default()
+    };
+    assert_eq!(part_1(&reports, &wide_steps), 4);
+    let level_ok = SafetyPolicy {
+        allow_zero_steps: true,
+        ..SafetyPolicy::default()
+    };
+    assert!(level_ok.is_safe(&[8, 6, 4, 4, 1]));
+    assert!(!SafetyPolicy::default().is_safe(&[8, 6, 4, 4, 1]));
+    assert!(SafetyPolicy::default().with_tolerance(1).is_safe(&[8, 6, 4, 4, 1]));
+
+    let explanations = explain_all(&reports, &SafetyPolicy::default());
+    assert_eq!(
+        explanations.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+        vec![1, 2, 3, 4]
+    );
+    assert_eq!(
+        explanations[0].1,
+        Explanation {
+            violation: Violation {
+                window: (1, 2),
+                levels: (2, 7),
+                rule: Rule::StepTooLarge { step: 5, max: 3 },
+            },
+            fix: vec![0, 1],
+        }
+    );
+    assert_eq!(explanations[2].1.violation.rule, Rule::WrongDirection { step: -1 });
+    assert_eq!(
+        explanations[3].1.to_string(),
+        "levels 2 and 3 (4 then 4): the level doesn't change; removing level [3] would fix it"
+    );
+    assert_eq!(SafetyPolicy::default().with_tolerance(1).explain(&[1, 3, 2, 4, 5]), None);
+
+    // with no limit, keeping the longest run agrees with the search that's kept to a window
+    let mut seed = 1u64;
+    let mut random = |below: u64| {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (seed >> 33) % below
+    };
+    let policies = [
+        SafetyPolicy::default(),
+        level_ok,
+        SafetyPolicy { min_step: 2, max_step: 4, ..SafetyPolicy::default() },
+        SafetyPolicy { max_step: i64::MAX, ..SafetyPolicy::default() },
+    ];
+    for _ in 0..500 {
+        let report = (0..random(12) + 1).map(|_| random(10) as i64).collect::<Vec<_>>();
+        for policy in &policies {
+            for direction in [1, -1] {
+                assert_eq!(
+                    policy.longest_kept_in_direction(&report, direction),
+                    policy.fewest_removals_in_direction(&report, report.len(), direction).unwrap(),
+                    "{:?} {:?}",
+                    policy,
+                    report
+                );
+            }
+        }
+    }
+
+    // thousands of levels, with every tenth one out of place
+    let report = (0..20_000)
+        .map(|i| if i % 10 == 5 { 0 } else { i })
+        .collect::<Vec<_>>();
+    let explanation = SafetyPolicy::default().explain(&report).unwrap();
+    assert_eq!(explanation.fix, (0..2000).map(|i| i * 10 + 5).collect::<Vec<_>>());
+
+    let big_steps = SafetyPolicy {
+        min_step: 2,
+        ..SafetyPolicy::default()
+    };
+    assert_eq!(
+        big_steps.first_violation(&[1, 3, 4]).map(|v| v.rule),
+        Some(Rule::StepTooSmall { step: 1, min: 2 })
+    );
+}
@@ -0,0 +1,580 @@
+use memchr::{memchr, memchr2, memchr3};
+use std::borrow::Cow;
+use std::io::{self, Read};
+use std::ops::{Range, RangeInclusive};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Arity {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    fn allows(self, operands: usize) -> bool {
+        match self {
+            Arity::Exactly(n) => operands == n,
+            Arity::AtLeast(n) => operands >= n,
+        }
+    }
+
+    fn allows_more_than(self, operands: usize) -> bool {
+        match self {
+            Arity::Exactly(n) => operands < n,
+            Arity::AtLeast(_) => true,
+        }
+    }
+}
+
+// what running an instruction does
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Effect {
+    // adds to the total - but only to part 2's while it's enabled
+    Value(i64),
+    Enable,
+    Disable,
+    Toggle,
+    Nothing,
+}
+
+// `name(operands)`, with each operand a plain number of so many digits
+#[derive(Clone, Debug)]
+pub struct InstructionDef {
+    pub name: &'static str,
+    pub arity: Arity,
+    pub digits: RangeInclusive<usize>,
+    pub semantics: fn(&[i64]) -> Effect,
+}
+
+impl InstructionDef {
+    pub const fn new(name: &'static str, arity: Arity, semantics: fn(&[i64]) -> Effect) -> Self {
+        Self {
+            name,
+            arity,
+            digits: 1..=3,
+            semantics,
+        }
+    }
+
+    pub const fn digits(self, digits: RangeInclusive<usize>) -> Self {
+        Self { digits, ..self }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct InstructionSet {
+    defs: Cow<'static, [InstructionDef]>,
+}
+
+// the puzzle's own instructions
+pub static STANDARD: InstructionSet = InstructionSet {
+    defs: Cow::Borrowed(&[
+        InstructionDef::new("mul", Arity::Exactly(2), |o| Effect::Value(o[0] * o[1])),
+        InstructionDef::new("do", Arity::Exactly(0), |_| Effect::Enable),
+        InstructionDef::new("don't", Arity::Exactly(0), |_| Effect::Disable),
+    ]),
+};
+
+// an instruction as it was found, and the bytes of the input it came from
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Instruction {
+    pub name: Cow<'static, str>,
+    pub operands: Vec<i64>,
+    pub span: Range<usize>,
+}
+
+enum Match {
+    Found(&'static str, Vec<i64>, usize),
+    Nothing,
+    // the bytes so far could still be the start of something
+    Incomplete,
+}
+
+// the operands and closing bracket after `name(`, and how many bytes they took
+fn match_operands(bytes: &[u8], def: &InstructionDef) -> Match {
+    let mut operands = Vec::new();
+    let mut position = 0;
+    loop {
+        if bytes.get(position) == Some(&b')') && operands.is_empty() && def.arity.allows(0) {
+            return Match::Found(def.name, operands, position + 1);
+        }
+
+        let digits = bytes[position..]
+            .iter()
+            .take(def.digits.end() + 1)
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        let Some(&next) = bytes.get(position + digits) else {
+            return Match::Incomplete;
+        };
+        if !def.digits.contains(&digits) {
+            return Match::Nothing;
+        }
+        let Some(value) = bytes[position..position + digits]
+            .iter()
+            .try_fold(0i64, |n, d| n.checked_mul(10)?.checked_add((d - b'0') as i64))
+        else {
+            return Match::Nothing;
+        };
+        operands.push(value);
+        position += digits + 1;
+
+        match next {
+            b')' if def.arity.allows(operands.len()) => {
+                return Match::Found(def.name, operands, position);
+            }
+            b',' if def.arity.allows_more_than(operands.len()) => {}
+            _ => return Match::Nothing,
+        }
+    }
+}
+
+impl InstructionSet {
+    pub fn new(defs: Vec<InstructionDef>) -> Self {
+        Self { defs: Cow::Owned(defs) }
+    }
+
+    // earlier instructions win when two could match at the same place
+    pub fn with(mut self, def: InstructionDef) -> Self {
+        self.defs.to_mut().push(def);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&InstructionDef> {
+        self.defs.iter().find(|d| d.name == name)
+    }
+
+    fn match_at(&self, bytes: &[u8]) -> Match {
+        let mut incomplete = false;
+        for def in self.defs.iter() {
+            let name = def.name.as_bytes();
+            let result = if bytes.len() <= name.len() {
+                if name.starts_with(bytes) { Match::Incomplete } else { Match::Nothing }
+            } else if bytes.starts_with(name) && bytes[name.len()] == b'(' {
+                match match_operands(&bytes[name.len() + 1..], def) {
+                    Match::Found(name, operands, len) => Match::Found(name, operands, def.name.len() + 1 + len),
+                    other => other,
+                }
+            } else {
+                Match::Nothing
+            };
+
+            match result {
+                Match::Found(..) => return result,
+                Match::Incomplete => incomplete = true,
+                Match::Nothing => {}
+            }
+        }
+
+        if incomplete { Match::Incomplete } else { Match::Nothing }
+    }
+
+    pub fn scanner<R: Read>(&self, reader: R) -> Scanner<'_, R> {
+        let mut first_bytes = self.defs.iter().filter_map(|d| d.name.bytes().next()).collect::<Vec<_>>();
+        first_bytes.sort();
+        first_bytes.dedup();
+        Scanner {
+            set: self,
+            first_bytes,
+            reader,
+            buffer: vec![0; BUFFER_SIZE].into_boxed_slice(),
+            offset: 0,
+            start: 0,
+            end: 0,
+            eof: false,
+        }
+    }
+
+    pub fn parse(&self, input: &str) -> Vec<Instruction> {
+        // reading from a slice can't fail
+        self.scanner(input.as_bytes()).map(Result::unwrap).collect()
+    }
+
+    // an instruction this set doesn't know about (from some other set, say) does nothing
+    pub fn effect(&self, instruction: &Instruction) -> Effect {
+        self.get(&instruction.name)
+            .map_or(Effect::Nothing, |def| (def.semantics)(&instruction.operands))
+    }
+
+    pub fn run(&self, input: &[Instruction]) -> Totals {
+        let mut totals = Totals::default();
+        for i in input {
+            totals.apply(self.effect(i));
+        }
+
+        totals
+    }
+
+    // answers both parts in one pass over the dump, without keeping any of it
+    pub fn solve_stream(&self, reader: impl Read) -> io::Result<Totals> {
+        let mut totals = Totals::default();
+        for instruction in self.scanner(reader) {
+            totals.apply(self.effect(&instruction?));
+        }
+
+        Ok(totals)
+    }
+
+    pub fn trace(&self, input: &[Instruction]) -> Vec<TraceStep> {
+        let mut totals = Totals::default();
+        input
+            .iter()
+            .map(|i| {
+                let before = totals.enabled;
+                let effect = self.effect(i);
+                totals.apply(effect);
+                TraceStep {
+                    instruction: i.clone(),
+                    effect,
+                    enabled: totals.include,
+                    contribution: totals.enabled - before,
+                    total: totals.enabled,
+                }
+            })
+            .collect()
+    }
+}
+
+// enough for anything the scanner has to look at in one go - an instruction
+// with more operands than this holds can't be matched
+const BUFFER_SIZE: usize = 64 * 1024;
+
+// pulls instructions out of the corrupted memory as it's read, so the whole dump
+// never has to be in memory at once
+pub struct Scanner<'a, R> {
+    set: &'a InstructionSet,
+    // the bytes an instruction can start with, to skip straight to the next one
+    first_bytes: Vec<u8>,
+    reader: R,
+    buffer: Box<[u8]>,
+    // how far into the input the start of the buffer is
+    offset: usize,
+    start: usize,
+    end: usize,
+    eof: bool,
+}
+
+impl<R: Read> Scanner<'static, R> {
+    pub fn new(reader: R) -> Self {
+        STANDARD.scanner(reader)
+    }
+}
+
+impl<R: Read> Scanner<'_, R> {
+    // keeps whatever hasn't been looked at yet, and tops the buffer up behind it
+    fn fill(&mut self) -> io::Result<()> {
+        self.buffer.copy_within(self.start..self.end, 0);
+        self.offset += self.start;
+        self.end -= self.start;
+        self.start = 0;
+        while !self.eof && self.end < self.buffer.len() {
+            match self.reader.read(&mut self.buffer[self.end..]) {
+                Ok(0) => self.eof = true,
+                Ok(n) => {
+                    self.end += n;
+                    break;
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn next_candidate(&self) -> Option<usize> {
+        let bytes = &self.buffer[self.start..self.end];
+        match self.first_bytes[..] {
+            [] => None,
+            [a] => memchr(a, bytes),
+            [a, b] => memchr2(a, b, bytes),
+            [a, b, c] => memchr3(a, b, c, bytes),
+            _ => bytes.iter().position(|b| self.first_bytes.contains(b)),
+        }
+    }
+}
+
+impl<R: Read> Iterator for Scanner<'_, R> {
+    type Item = io::Result<Instruction>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some(offset) = self.next_candidate() else {
+                // nothing worth keeping in what's left
+                self.start = self.end;
+                if self.eof {
+                    return None;
+                }
+                if let Err(e) = self.fill() {
+                    return Some(Err(e));
+                }
+                continue;
+            };
+            self.start += offset;
+
+            // a full buffer can't take any more, so whatever's in it has to do
+            let full = self.start == 0 && self.end == self.buffer.len();
+            match self.set.match_at(&self.buffer[self.start..self.end]) {
+                Match::Found(name, operands, len) => {
+                    let start = self.offset + self.start;
+                    self.start += len;
+                    return Some(Ok(Instruction {
+                        name: Cow::Borrowed(name),
+                        operands,
+                        span: start..start + len,
+                    }));
+                }
+                Match::Incomplete if !self.eof && !full => {
+                    if let Err(e) = self.fill() {
+                        return Some(Err(e));
+                    }
+                }
+                Match::Incomplete | Match::Nothing => self.start += 1,
+            }
+        }
+    }
+}
+
+pub use common::ParseError;
+
+pub fn try_parse_input(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    Scanner::new(input.as_bytes())
+        .collect::<io::Result<_>>()
+        .map_err(|e| ParseError(e.to_string()))
+}
+
+pub fn parse_input(input: &str) -> Vec<Instruction> {
+    try_parse_input(input).unwrap()
+}
+
+// both parts' answers at once, with and without the enabling and disabling
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Totals {
+    pub all: i64,
+    pub enabled: i64,
+    include: bool,
+}
+
+impl Default for Totals {
+    fn default() -> Self {
+        Self {
+            all: 0,
+            enabled: 0,
+            include: true,
+        }
+    }
+}
+
+impl Totals {
+    pub fn apply(&mut self, effect: Effect) {
+        match effect {
+            Effect::Value(value) => {
+                self.all += value;
+                if self.include {
+                    self.enabled += value;
+                }
+            }
+            Effect::Enable => self.include = true,
+            Effect::Disable => self.include = false,
+            Effect::Toggle => self.include = !self.include,
+            Effect::Nothing => {}
+        }
+    }
+}
+
+pub fn solve_stream(reader: impl Read) -> io::Result<Totals> {
+    STANDARD.solve_stream(reader)
+}
+
+pub fn part_1(input: &[Instruction]) -> i64 {
+    STANDARD.run(input).all
+}
+
+pub fn part_2(input: &[Instruction]) -> i64 {
+    STANDARD.run(input).enabled
+}
+
+// what happened to one instruction on the way through part 2
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceStep {
+    pub instruction: Instruction,
+    pub effect: Effect,
+    // whether values counted at this point
+    pub enabled: bool,
+    pub contribution: i64,
+    pub total: i64,
+}
+
+pub fn trace(input: &[Instruction]) -> Vec<TraceStep> {
+    STANDARD.trace(input)
+}
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const CYAN: &str = "\x1b[36m";
+const YELLOW: &str = "\x1b[33m";
+const MAGENTA: &str = "\x1b[35m";
+const RESET: &str = "\x1b[0m";
+
+// the input again, with counted values in green, ignored ones in red, enabling in cyan,
+// disabling in yellow and toggling in magenta
+pub fn render_trace(input: &str, trace: &[TraceStep]) -> String {
+    let mut out = String::new();
+    let mut position = 0;
+    for step in trace {
+        let span = step.instruction.span.clone();
+        let colour = match step.effect {
+            Effect::Value(_) if step.enabled => GREEN,
+            Effect::Value(_) => RED,
+            Effect::Enable => CYAN,
+            Effect::Disable => YELLOW,
+            Effect::Toggle => MAGENTA,
+            Effect::Nothing => "",
+        };
+        out.push_str(&input[position..span.start]);
+        out.push_str(colour);
+        out.push_str(&input[span.clone()]);
+        if !colour.is_empty() {
+            out.push_str(RESET);
+        }
+        position = span.end;
+    }
+    out.push_str(&input[position..]);
+
+    out
+}
+
+pub fn trace_table(input: &str, trace: &[TraceStep]) -> String {
+    let mut out = format!("{:>14}  {:14}  {:8}  {:>12}  {:>12}\n", "bytes", "instruction", "enabled", "adds", "total");
+    for step in trace {
+        let span = &step.instruction.span;
+        out.push_str(&format!(
+            "{:>14}  {:14}  {:8}  {:>12}  {:>12}\n",
+            format!("{}..{}", span.start, span.end),
+            &input[span.clone()],
+            if step.enabled { "yes" } else { "no" },
+            step.contribution,
+            step.total
+        ));
+    }
+
+    out
+}
+
+#[test]
+pub fn test() {
+    let input = r#"xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))"#;
+    let muls = parse_input(input.trim());
+    assert_eq!(part_1(&muls), 161);
+
+    let input_2 = r#"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"#;
+    let muls = parse_input(input_2.trim());
+    assert_eq!(part_2(&muls), 48);
+}
+
+// hands over a byte at a time, so every instruction gets split between reads
+#[cfg(test)]
+struct Trickle<'a>(&'a [u8]);
+
+#[cfg(test)]
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some((first, rest)) = self.0.split_first() else {
+            return Ok(0);
+        };
+        buf[0] = *first;
+        self.0 = rest;
+        Ok(1)
+    }
+}
+
+#[cfg(test)]
+fn instruction(name: &'static str, operands: &[i64], span: Range<usize>) -> Instruction {
+    Instruction {
+        name: Cow::Borrowed(name),
+        operands: operands.to_vec(),
+        span,
+    }
+}
+
+#[test]
+pub fn test_scanner() {
+    let input = r#"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))mul(1234,5)mul(12"#;
+    let expected = vec![
+        instruction("mul", &[2, 4], 1..9),
+        instruction("don't", &[], 20..27),
+        instruction("mul", &[5, 5], 28..36),
+        instruction("mul", &[11, 8], 48..57),
+        instruction("do", &[], 59..63),
+        instruction("mul", &[8, 5], 64..72),
+    ];
+    assert_eq!(parse_input(input), expected);
+    let trickled = Scanner::new(Trickle(input.as_bytes()))
+        .collect::<io::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(trickled, expected);
+
+    // far more than the buffer holds, with an instruction straddling every refill
+    let junk = io::repeat(b'd').take(10 * BUFFER_SIZE as u64 - 3);
+    let dump = junk.chain(&b"mul(7,6)"[..]).chain(io::repeat(b'm').take(BUFFER_SIZE as u64));
+    let items = Scanner::new(dump).collect::<io::Result<Vec<_>>>().unwrap();
+    assert_eq!(items, vec![instruction("mul", &[7, 6], 10 * BUFFER_SIZE - 3..10 * BUFFER_SIZE + 5)]);
+
+    let totals = solve_stream(Trickle(input.as_bytes())).unwrap();
+    assert_eq!((totals.all, totals.enabled), (161, 48));
+
+    assert_eq!(parse_input(""), vec![]);
+}
+
+#[test]
+pub fn test_trace() {
+    let input = "xmul(2,4)&don't()_mul(5,5)+do()?mul(8,5))";
+    let trace = trace(&parse_input(input));
+    assert_eq!(
+        trace.iter().map(|s| (s.enabled, s.contribution, s.total)).collect::<Vec<_>>(),
+        vec![(true, 8, 8), (false, 0, 8), (false, 0, 8), (true, 0, 8), (true, 40, 48)]
+    );
+
+    assert_eq!(
+        render_trace(input, &trace),
+        "x\x1b[32mmul(2,4)\x1b[0m&\x1b[33mdon't()\x1b[0m_\x1b[31mmul(5,5)\x1b[0m+\x1b[36mdo()\x1b[0m?\x1b[32mmul(8,5)\x1b[0m)"
+    );
+
+    let table = trace_table(input, &trace);
+    assert_eq!(table.lines().count(), 6);
+    assert_eq!(table.lines().nth(3).unwrap().split_whitespace().collect::<Vec<_>>(), ["18..26", "mul(5,5)", "no", "0", "8"]);
+}
+
+#[test]
+pub fn test_instruction_set() {
+    let set = STANDARD
+        .clone()
+        .with(InstructionDef::new("add", Arity::Exactly(2), |o| Effect::Value(o[0] + o[1])))
+        .with(InstructionDef::new("sub", Arity::Exactly(2), |o| Effect::Value(o[0] - o[1])))
+        .with(InstructionDef::new("sum", Arity::AtLeast(1), |o| Effect::Value(o.iter().sum())).digits(1..=5))
+        // only turns things off when given a zero
+        .with(InstructionDef::new("when", Arity::Exactly(1), |o| {
+            if o[0] == 0 { Effect::Disable } else { Effect::Enable }
+        }))
+        .with(InstructionDef::new("flip", Arity::Exactly(0), |_| Effect::Toggle));
+
+    let input = "add(1,2)sub(10,4)x:sum(1,2,3,40000)sum()sum(1,)when(0)mul(5,5)when(7)add(1,1)flip()mul(2,2)flip()mul(3,3)";
+    let instructions = set.parse(input);
+    assert_eq!(
+        instructions.iter().map(|i| i.name.as_ref()).collect::<Vec<_>>(),
+        ["add", "sub", "sum", "when", "mul", "when", "add", "flip", "mul", "flip", "mul"]
+    );
+    assert_eq!(instructions[2].operands, vec![1, 2, 3, 40000]);
+
+    let totals = set.run(&instructions);
+    assert_eq!(totals.all, 3 + 6 + 40006 + 25 + 2 + 4 + 9);
+    assert_eq!(totals.enabled, 3 + 6 + 40006 + 2 + 9);
+    assert_eq!(set.solve_stream(Trickle(input.as_bytes())).unwrap(), totals);
+
+    // the standard set doesn't know the new instructions, even if they were parsed
+    assert_eq!(STANDARD.run(&instructions).all, 25 + 4 + 9);
+    assert_eq!(
+        render_trace(input, &set.trace(&instructions[7..9])),
+        input.replace("flip()mul(2,2)", "\x1b[35mflip()\x1b[0m\x1b[31mmul(2,2)\x1b[0m")
+    );
+}
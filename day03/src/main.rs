@@ -1,86 +1,28 @@
-use winnow::combinator::{alt, delimited, repeat, separated_pair};
-use winnow::stream::AsChar;
-use winnow::token::{take, take_while};
-use winnow::{PResult, Parser};
-
-#[derive(Copy, Clone, Debug)]
-pub enum ItemOfInterest {
-    Multiplication(i64, i64),
-    Do,
-    Dont,
-}
-
-pub fn parse_num(input: &mut &str) -> PResult<i64> {
-    take_while(1..=3, AsChar::is_dec_digit)
-        .parse_to()
-        .parse_next(input)
-}
-
-pub fn parse_items(input: &mut &str) -> PResult<Vec<Option<ItemOfInterest>>> {
-    repeat(
-        1..,
-        alt((
-            delimited("mul(", separated_pair(parse_num, ",", parse_num), ")")
-                .map(|(left, right)| Some(ItemOfInterest::Multiplication(left, right))),
-            "do()".map(|_| Some(ItemOfInterest::Do)),
-            "don't()".map(|_| Some(ItemOfInterest::Dont)),
-            take(1usize).map(|_| None),
-        )),
-    )
-    .parse_next(input)
-}
-
-pub fn parse_input(input: &str) -> Vec<ItemOfInterest> {
-    parse_items
-        .parse(input.trim())
-        .unwrap()
-        .into_iter()
-        .flatten()
-        .collect()
-}
-
-pub fn part_1(input: &[ItemOfInterest]) -> i64 {
-    input
-        .iter()
-        .filter_map(|i| {
-            if let ItemOfInterest::Multiplication(left, right) = i {
-                Some(left * right)
-            } else {
-                None
-            }
-        })
-        .sum()
-}
-
-pub fn part_2(input: &[ItemOfInterest]) -> i64 {
-    let mut include = true;
-    let mut result = 0;
-    for i in input {
-        match i {
-            ItemOfInterest::Multiplication(left, right) if include => result += left * right,
-            ItemOfInterest::Multiplication(_, _) => {}
-            ItemOfInterest::Do => include = true,
-            ItemOfInterest::Dont => include = false,
-        }
-    }
-
-    result
-}
+use day03::{parse_input, part_1, part_2, render_trace, trace, trace_table};
 
 fn main() {
     let file = include_str!("../input.txt");
     let input = parse_input(file);
+    // --trace shows what happened to every instruction instead of the answers
+    if std::env::args().any(|a| a == "--trace") {
+        // the trace is drawn over input.txt, which a model from json doesn't match
+        #[cfg(feature = "serde")]
+        if std::env::args().any(|a| a == "--from-json" || a == "--dump-json") {
+            eprintln!("--trace can't be combined with --from-json or --dump-json");
+            std::process::exit(1);
+        }
+        let trace = trace(&input);
+        println!("{}", render_trace(file, &trace));
+        print!("{}", trace_table(file, &trace));
+        return;
+    }
+    #[cfg(feature = "serde")]
+    let Some(input) = common::apply_json_args(input).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }) else {
+        return;
+    };
     println!("Part 1: {}", part_1(&input));
     println!("Part 2: {}", part_2(&input));
 }
-
-#[test]
-pub fn test() {
-    let input = r#"xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))"#;
-    let muls = parse_input(input.trim());
-    assert_eq!(part_1(&muls), 161);
-
-    let input_2 = r#"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"#;
-    let muls = parse_input(input_2.trim());
-    assert_eq!(part_2(&muls), 48);
-}
@@ -0,0 +1,530 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead};
+use std::ops::Add;
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Coord {
+    x: i64,
+    y: i64,
+}
+
+impl From<(i64, i64)> for Coord {
+    fn from((x, y): (i64, i64)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<Coord> for (i64, i64) {
+    fn from(coord: Coord) -> Self {
+        (coord.x, coord.y)
+    }
+}
+
+impl Add<(i64, i64)> for Coord {
+    type Output = Coord;
+
+    fn add(self, (x, y): (i64, i64)) -> Self::Output {
+        Self::Output {
+            x: self.x + x,
+            y: self.y + y,
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Input {
+    #[cfg_attr(feature = "serde", serde(with = "common::map_as_pairs"))]
+    grid: HashMap<Coord, char>,
+}
+
+pub use common::ParseError;
+
+// any grid of letters will do
+pub fn try_parse_input(input: &str) -> Result<Input, ParseError> {
+    Ok(Input {
+        grid: input
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(move |(x, c)| ((x as i64, y as i64).into(), c))
+            })
+            .collect(),
+    })
+}
+
+pub fn parse_input(input: &str) -> Input {
+    try_parse_input(input).unwrap()
+}
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    pub fn offset(self) -> (i64, i64) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, -1),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    pub case_insensitive: bool,
+    // words can run off one edge of the grid and carry on from the other
+    pub wrap: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WordMatch {
+    pub word: String,
+    pub start: Coord,
+    pub direction: Direction,
+    // where the last letter is - which with wrapping needn't be in a straight line from the start
+    pub end: Coord,
+}
+
+#[derive(Debug, Default)]
+struct TrieNode {
+    children: HashMap<char, usize>,
+    // the index of the word that ends here
+    word: Option<usize>,
+}
+
+// every word at once - walking out from a cell follows the trie, and stops as
+// soon as no word carries on that way
+#[derive(Debug)]
+pub struct WordSearch {
+    nodes: Vec<TrieNode>,
+    words: Vec<String>,
+    longest: usize,
+    options: SearchOptions,
+}
+
+impl WordSearch {
+    pub fn new<S: AsRef<str>>(words: impl IntoIterator<Item = S>, options: SearchOptions) -> Self {
+        let mut search = Self {
+            nodes: vec![TrieNode::default()],
+            words: Vec::new(),
+            longest: 0,
+            options,
+        };
+        for word in words {
+            search.insert(word.as_ref());
+        }
+
+        search
+    }
+
+    fn fold(&self, c: char) -> char {
+        if self.options.case_insensitive {
+            c.to_lowercase().next().unwrap_or(c)
+        } else {
+            c
+        }
+    }
+
+    fn insert(&mut self, word: &str) {
+        let mut node = 0;
+        let mut length = 0;
+        for c in word.chars() {
+            let c = self.fold(c);
+            let next = self.nodes.len();
+            node = *self.nodes[node].children.entry(c).or_insert(next);
+            if node == next {
+                self.nodes.push(TrieNode::default());
+            }
+            length += 1;
+        }
+
+        // the same word twice (or in a different case) is only looked for once
+        if length > 0 && self.nodes[node].word.is_none() {
+            self.nodes[node].word = Some(self.words.len());
+            self.words.push(word.to_string());
+            self.longest = self.longest.max(length);
+        }
+    }
+
+    // in reading order of where they start
+    pub fn find(&self, input: &Input) -> Vec<WordMatch> {
+        let (width, height) = input.dimensions();
+        let step = |coord: Coord, (dx, dy): (i64, i64)| {
+            let next = coord + (dx, dy);
+            if self.options.wrap {
+                (next.x.rem_euclid(width), next.y.rem_euclid(height)).into()
+            } else {
+                next
+            }
+        };
+
+        let mut matches = Vec::new();
+        for start in input.grid.keys() {
+            for direction in Direction::ALL {
+                let mut node = 0;
+                let mut coord = *start;
+                for length in 1..=self.longest {
+                    let Some(c) = input.grid.get(&coord) else {
+                        break;
+                    };
+                    let Some(next) = self.nodes[node].children.get(&self.fold(*c)) else {
+                        break;
+                    };
+                    node = *next;
+                    // a single letter reads the same every way, so it only counts going north
+                    if let Some(word) = self.nodes[node].word
+                        && (length > 1 || direction == Direction::North)
+                    {
+                        matches.push(WordMatch {
+                            word: self.words[word].clone(),
+                            start: *start,
+                            direction,
+                            end: coord,
+                        });
+                    }
+                    coord = step(coord, direction.offset());
+                }
+            }
+        }
+
+        matches.sort_by_key(|m| (m.start.y, m.start.x, m.direction, m.word.clone()));
+        matches
+    }
+}
+
+// one word to a line, ignoring blank lines
+pub fn read_words(reader: impl BufRead) -> io::Result<Vec<String>> {
+    let mut words = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let word = line.trim();
+        if !word.is_empty() {
+            words.push(word.to_string());
+        }
+    }
+
+    Ok(words)
+}
+
+impl Input {
+    // rows needn't all be the same length, so this is the longest of them
+    pub fn dimensions(&self) -> (i64, i64) {
+        self.grid
+            .keys()
+            .fold((0, 0), |(w, h), c| (w.max(c.x + 1), h.max(c.y + 1)))
+    }
+}
+
+pub fn part_1(input: &Input) -> usize {
+    WordSearch::new(["XMAS"], SearchOptions::default()).find(input).len()
+}
+
+// a small picture to look for, with wildcards - written a row at a time, with the
+// rows split by '/' or new lines and '.' matching anything
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Template {
+    width: i64,
+    height: i64,
+    // just the cells that have to match, in order
+    cells: Vec<(i64, i64, char)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Placement {
+    // where the top left corner of the template went
+    pub origin: Coord,
+    // which of the template's orientations it was
+    pub orientation: usize,
+    // the grid cells that matched something other than a wildcard
+    pub cells: Vec<Coord>,
+}
+
+impl Template {
+    pub fn parse(pattern: &str) -> Result<Self, ParseError> {
+        // a pattern read from a file can end in a newline, but a blank row anywhere else would
+        // change the template's shape
+        let rows = pattern.strip_suffix('\n').unwrap_or(pattern).split(['/', '\n']).collect::<Vec<_>>();
+        if let Some(y) = rows.iter().position(|r| r.is_empty()) {
+            return Err(ParseError(format!("row {} of {:?} is blank - use dots for empty cells", y + 1, pattern)));
+        }
+        let mut cells = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c != '.' {
+                    cells.push((x as i64, y as i64, c));
+                }
+            }
+        }
+        if cells.is_empty() {
+            return Err(ParseError(format!("{:?} would match anywhere", pattern)));
+        }
+
+        Ok(Self::new(cells))
+    }
+
+    // shifted so the top left is at 0, 0 - otherwise turning it around would move it
+    fn new(mut cells: Vec<(i64, i64, char)>) -> Self {
+        let min_x = cells.iter().map(|c| c.0).min().unwrap_or(0);
+        let min_y = cells.iter().map(|c| c.1).min().unwrap_or(0);
+        for cell in &mut cells {
+            cell.0 -= min_x;
+            cell.1 -= min_y;
+        }
+        cells.sort();
+
+        Self {
+            width: cells.iter().map(|c| c.0 + 1).max().unwrap_or(0),
+            height: cells.iter().map(|c| c.1 + 1).max().unwrap_or(0),
+            cells,
+        }
+    }
+
+    // a quarter turn clockwise
+    pub fn rotate(&self) -> Self {
+        Self::new(self.cells.iter().map(|&(x, y, c)| (self.height - 1 - y, x, c)).collect())
+    }
+
+    // flipped left to right
+    pub fn reflect(&self) -> Self {
+        Self::new(self.cells.iter().map(|&(x, y, c)| (self.width - 1 - x, y, c)).collect())
+    }
+
+    // every way the template can be turned or flipped, without repeats - a symmetric
+    // template has fewer than eight
+    pub fn orientations(&self) -> Vec<Template> {
+        let mut orientations = Vec::new();
+        let mut template = self.clone();
+        for _ in 0..4 {
+            for t in [template.clone(), template.reflect()] {
+                if !orientations.contains(&t) {
+                    orientations.push(t);
+                }
+            }
+            template = template.rotate();
+        }
+
+        orientations
+    }
+
+    fn matches_at(&self, input: &Input, origin: Coord) -> Option<Vec<Coord>> {
+        self.cells
+            .iter()
+            .map(|&(x, y, c)| {
+                let coord = origin + (x, y);
+                (input.grid.get(&coord) == Some(&c)).then_some(coord)
+            })
+            .collect()
+    }
+
+    // every placement of every orientation, in reading order of where they go
+    pub fn placements(&self, input: &Input) -> Vec<Placement> {
+        let mut placements = Vec::new();
+        for (orientation, template) in self.orientations().iter().enumerate() {
+            for origin in input.grid.keys() {
+                if let Some(cells) = template.matches_at(input, *origin) {
+                    placements.push(Placement {
+                        origin: *origin,
+                        orientation,
+                        cells,
+                    });
+                }
+            }
+        }
+
+        placements.sort_by_key(|p| (p.origin.y, p.origin.x, p.orientation));
+        placements
+    }
+
+    pub fn count(&self, input: &Input) -> usize {
+        self.orientations()
+            .iter()
+            .map(|template| {
+                input
+                    .grid
+                    .keys()
+                    .filter(|origin| template.matches_at(input, **origin).is_some())
+                    .count()
+            })
+            .sum()
+    }
+}
+
+impl std::fmt::Display for Template {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            if y > 0 {
+                write!(f, "/")?;
+            }
+            for x in 0..self.width {
+                let c = self.cells.iter().find(|c| c.0 == x && c.1 == y).map_or('.', |c| c.2);
+                write!(f, "{}", c)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// two MASes crossing on the A
+pub fn part_2(input: &Input) -> usize {
+    Template::parse("M.S/.A./M.S").unwrap().count(input)
+}
+
+#[test]
+pub fn test() {
+    let input = r#"MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX
+"#;
+    let grid = parse_input(input);
+    assert_eq!(part_1(&grid), 18);
+    assert_eq!(part_2(&grid), 9);
+}
+
+#[test]
+pub fn test_word_search() {
+    let grid = parse_input(
+        "abcd
+efgh
+ijkl
+",
+    );
+
+    let search = WordSearch::new(["BCD", "dcb", "afk", "kl", "lia"], SearchOptions::default());
+    assert_eq!(
+        search.find(&grid),
+        vec![
+            WordMatch {
+                word: "afk".to_string(),
+                start: (0, 0).into(),
+                direction: Direction::SouthEast,
+                end: (2, 2).into(),
+            },
+            WordMatch {
+                word: "dcb".to_string(),
+                start: (3, 0).into(),
+                direction: Direction::West,
+                end: (1, 0).into(),
+            },
+            WordMatch {
+                word: "kl".to_string(),
+                start: (2, 2).into(),
+                direction: Direction::East,
+                end: (3, 2).into(),
+            },
+        ]
+    );
+
+    let search = WordSearch::new(
+        ["BCD", "lij", "abcda", "hef"],
+        SearchOptions {
+            case_insensitive: true,
+            wrap: true,
+        },
+    );
+    let found = search
+        .find(&grid)
+        .into_iter()
+        .map(|m| (m.word, m.start.into(), m.direction, m.end.into()))
+        .collect::<Vec<(String, (i64, i64), Direction, (i64, i64))>>();
+    assert_eq!(
+        found,
+        vec![
+            ("abcda".to_string(), (0, 0), Direction::East, (0, 0)),
+            ("BCD".to_string(), (1, 0), Direction::East, (3, 0)),
+            ("hef".to_string(), (3, 1), Direction::East, (1, 1)),
+            ("lij".to_string(), (3, 2), Direction::East, (1, 2)),
+        ]
+    );
+
+    // one letter is one match, not eight
+    let search = WordSearch::new(["e", "ij"], SearchOptions::default());
+    let found = search
+        .find(&grid)
+        .into_iter()
+        .map(|m| (m.word, m.start.into(), m.direction))
+        .collect::<Vec<(String, (i64, i64), Direction)>>();
+    assert_eq!(
+        found,
+        vec![
+            ("e".to_string(), (0, 1), Direction::North),
+            ("ij".to_string(), (0, 2), Direction::East),
+        ]
+    );
+
+    let words = read_words("XMAS\n\n  SAMX \n".as_bytes()).unwrap();
+    assert_eq!(words, vec!["XMAS", "SAMX"]);
+}
+
+#[test]
+pub fn test_template() {
+    let x_mas = Template::parse("M.S/.A./M.S").unwrap();
+    assert_eq!(
+        x_mas.orientations().iter().map(|t| t.to_string()).collect::<Vec<_>>(),
+        ["M.S/.A./M.S", "S.M/.A./S.M", "M.M/.A./S.S", "S.S/.A./M.M"]
+    );
+    // nothing to tell apart after turning it around
+    assert_eq!(Template::parse(".A./AAA/.A.").unwrap().orientations().len(), 1);
+    assert_eq!(Template::parse("AB").unwrap().orientations().len(), 4);
+    assert_eq!(Template::parse("AB/C.").unwrap().orientations().len(), 8);
+    assert!(Template::parse("../..").is_err());
+    assert_eq!(Template::parse("AB\nC.\n").unwrap(), Template::parse("AB/C.").unwrap());
+    assert!(Template::parse("AB//C.").is_err());
+    assert!(Template::parse("/AB").is_err());
+
+    let grid = parse_input(
+        "M.S.
+.A..
+MSSM
+..A.
+.S.M
+",
+    );
+    let placements = x_mas.placements(&grid);
+    assert_eq!(placements.len(), 2);
+    assert_eq!(placements[0].origin, (0, 0).into());
+    assert_eq!(placements[1].origin, (1, 2).into());
+    assert_eq!(x_mas.orientations()[placements[1].orientation].to_string(), "S.M/.A./S.M");
+    assert!(placements[1].cells.contains(&(2, 3).into()));
+    assert_eq!(x_mas.count(&grid), 2);
+
+    // a plus needs no more code than a cross
+    let plus = Template::parse(".X./XOX/.X.").unwrap();
+    assert_eq!(plus.count(&parse_input(".X.\nXOX\n.X.\n")), 1);
+}
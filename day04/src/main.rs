@@ -1,159 +1,60 @@
-use std::collections::HashMap;
-use std::ops::Add;
-
-#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
-pub struct Coord {
-    x: i64,
-    y: i64,
-}
-
-impl From<(i64, i64)> for Coord {
-    fn from((x, y): (i64, i64)) -> Self {
-        Self { x, y }
-    }
-}
-
-impl Add<(i64, i64)> for Coord {
-    type Output = Coord;
-
-    fn add(self, (x, y): (i64, i64)) -> Self::Output {
-        Self::Output {
-            x: self.x + x,
-            y: self.y + y,
+use day04::{SearchOptions, Template, WordSearch, parse_input, part_1, part_2, read_words};
+
+const USAGE: &str = "usage: day04 [--words <file> [--ignore-case] [--wrap] | --template <pattern>]";
+
+// the value after `flag`, if the flag's there at all
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    let index = args.iter().position(|a| a == flag)?;
+    match args.get(index + 1) {
+        Some(value) => Some(value),
+        None => {
+            eprintln!("{} needs a value\n{}", flag, USAGE);
+            std::process::exit(1);
         }
     }
 }
 
-impl Coord {
-    // for 'starting with X'; get the next 3 in all 8 directions
-    pub fn rays_from(self) -> Vec<[Coord; 3]> {
-        vec![
-            [self + (0, 1), self + (0, 2), self + (0, 3)],
-            [self + (1, 1), self + (2, 2), self + (3, 3)],
-            [self + (1, 0), self + (2, 0), self + (3, 0)],
-            [self + (1, -1), self + (2, -2), self + (3, -3)],
-            [self + (0, -1), self + (0, -2), self + (0, -3)],
-            [self + (-1, -1), self + (-2, -2), self + (-3, -3)],
-            [self + (-1, 0), self + (-2, 0), self + (-3, 0)],
-            [self + (-1, 1), self + (-2, 2), self + (-3, 3)],
-        ]
-    }
-
-    // for 'A in the middle'; get the four ways the surrounding corner cells
-    // can be arranged with the first two on the same side
-    pub fn corners_around(self) -> Vec<[Coord; 4]> {
-        vec![
-            [
-                self + (-1, -1),
-                self + (-1, 1),
-                self + (1, -1),
-                self + (1, 1),
-            ],
-            [
-                self + (1, -1),
-                self + (1, 1),
-                self + (-1, -1),
-                self + (-1, 1),
-            ],
-            [
-                self + (-1, -1),
-                self + (1, -1),
-                self + (-1, 1),
-                self + (1, 1),
-            ],
-            [
-                self + (1, 1),
-                self + (-1, 1),
-                self + (1, -1),
-                self + (-1, -1),
-            ],
-        ]
-    }
-}
-
-#[derive(Debug)]
-pub struct Input {
-    grid: HashMap<Coord, char>,
-}
-
-pub fn parse_input(input: &str) -> Input {
-    Input {
-        grid: input
-            .lines()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.chars()
-                    .enumerate()
-                    .map(move |(x, c)| ((x as i64, y as i64).into(), c))
-            })
-            .collect(),
-    }
-}
-
-pub fn part_1(input: &Input) -> usize {
-    let mut count = 0;
-    for (coord, c) in &input.grid {
-        if c != &'X' {
-            continue;
-        }
-
-        for ray in coord.rays_from() {
-            if let (Some('M'), Some('A'), Some('S')) = (
-                input.grid.get(&ray[0]),
-                input.grid.get(&ray[1]),
-                input.grid.get(&ray[2]),
-            ) {
-                count += 1
-            }
+fn main() {
+    let file = include_str!("../input.txt");
+    let input = parse_input(file);
+    // --words <file> looks for every word in the file instead, along with
+    // --ignore-case and --wrap
+    let args = std::env::args().collect::<Vec<_>>();
+    if let Some(path) = flag_value(&args, "--words") {
+        let words = std::fs::File::open(path).map(std::io::BufReader::new).and_then(read_words).unwrap_or_else(|e| {
+            eprintln!("couldn't read words from {}: {}\n{}", path, e, USAGE);
+            std::process::exit(1);
+        });
+        let options = SearchOptions {
+            case_insensitive: args.iter().any(|a| a == "--ignore-case"),
+            wrap: args.iter().any(|a| a == "--wrap"),
+        };
+        for m in WordSearch::new(words, options).find(&input) {
+            let (start, end): ((i64, i64), (i64, i64)) = (m.start.into(), m.end.into());
+            println!("{} at {:?} going {:?} to {:?}", m.word, start, m.direction, end);
         }
+        return;
     }
-
-    count
-}
-
-pub fn part_2(input: &Input) -> usize {
-    let mut count = 0;
-    for (coord, c) in &input.grid {
-        if c != &'A' {
-            continue;
-        }
-
-        for corners in coord.corners_around() {
-            if let (Some('M'), Some('M'), Some('S'), Some('S')) = (
-                input.grid.get(&corners[0]),
-                input.grid.get(&corners[1]),
-                input.grid.get(&corners[2]),
-                input.grid.get(&corners[3]),
-            ) {
-                count += 1
-            }
+    // --template <pattern> lists everywhere the pattern fits, turned or flipped any way
+    if let Some(pattern) = flag_value(&args, "--template") {
+        let template = Template::parse(pattern).unwrap_or_else(|e| {
+            eprintln!("{}\n{}", e, USAGE);
+            std::process::exit(1);
+        });
+        let orientations = template.orientations();
+        for p in template.placements(&input) {
+            let origin: (i64, i64) = p.origin.into();
+            println!("{} at {:?}", orientations[p.orientation], origin);
         }
+        return;
     }
-
-    count
-}
-
-fn main() {
-    let file = include_str!("../input.txt");
-    let input = parse_input(file);
+    #[cfg(feature = "serde")]
+    let Some(input) = common::apply_json_args(input).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }) else {
+        return;
+    };
     println!("Part 1: {}", part_1(&input));
     println!("Part 2: {}", part_2(&input));
 }
-
-#[test]
-pub fn test() {
-    let input = r#"MMMSXXMASM
-MSAMXMSMSA
-AMXSXMAAMM
-MSAMASMSMX
-XMASAMXAMM
-XXAMMXXAMA
-SMSMSASXSS
-SAXAMASAAA
-MAMMMXMMMM
-MXMXAXMASX
-"#;
-    let grid = parse_input(input);
-    assert_eq!(part_1(&grid), 18);
-    assert_eq!(part_2(&grid), 9);
-}
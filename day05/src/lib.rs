@@ -0,0 +1,961 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+};
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedInput"))]
+pub struct Input {
+    rules: HashMap<i64, HashSet<i64>>,
+    updates: Vec<Vec<i64>>,
+}
+
+// loaded from json rather than parsed, so nothing has made sure every update has a page in it
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedInput {
+    rules: HashMap<i64, HashSet<i64>>,
+    updates: Vec<Vec<i64>>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedInput> for Input {
+    type Error = ParseError;
+
+    fn try_from(UncheckedInput { rules, updates }: UncheckedInput) -> Result<Self, ParseError> {
+        if let Some(update) = updates.iter().position(|u| u.is_empty()) {
+            return Err(ParseError(format!("update {} has no pages", update + 1)));
+        }
+
+        Ok(Input { rules, updates })
+    }
+}
+
+// the rules that matter for one update: only the ones between pages it actually contains
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UpdateGraph {
+    // in the order the update lists them
+    pages: Vec<i64>,
+    // after[i] are the indices of the pages that have to come after pages[i]
+    after: Vec<Vec<usize>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OrderError {
+    RepeatedPage(i64),
+    // the rules between the update's pages loop back on themselves, so nothing can go first
+    Cyclic(Cycle),
+}
+
+impl std::fmt::Display for OrderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderError::RepeatedPage(page) => write!(f, "page {} is in the update more than once", page),
+            OrderError::Cyclic(cycle) => write!(f, "the rules form a cycle: {}", cycle),
+        }
+    }
+}
+
+impl std::error::Error for OrderError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sorted {
+    pub order: Vec<i64>,
+    // two pages that no rule orders, not even through other pages in the update;
+    // when there's one of these, more than one order is valid
+    pub unrelated: Option<(i64, i64)>,
+}
+
+impl Sorted {
+    pub fn is_determined(&self) -> bool {
+        self.unrelated.is_none()
+    }
+
+    pub fn middle(&self) -> i64 {
+        self.order[self.order.len() / 2]
+    }
+}
+
+// a strongly connected group of pages: each one has to come both before and after the others
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    // every page caught up in it, smallest first
+    pub pages: Vec<i64>,
+    // one of the shortest loops through it, e.g. [47, 53, 29] for 47|53, 53|29 and 29|47
+    pub evidence: Vec<i64>,
+}
+
+impl std::fmt::Display for Cycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for page in &self.evidence {
+            write!(f, "{} -> ", page)?;
+        }
+        write!(f, "{}", self.evidence[0])
+    }
+}
+
+// Tarjan's algorithm, keeping only the components that actually loop
+// (more than one page, or a page with a rule about itself)
+fn components(after: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut index: Vec<Option<usize>> = vec![None; after.len()];
+    let mut low = vec![0; after.len()];
+    let mut on_stack = vec![false; after.len()];
+    let mut stack = Vec::new();
+    let mut next = 0;
+    let mut found = Vec::new();
+
+    // the pages being visited, each with how many of its rules have been followed so far -
+    // kept by hand rather than recursing, since a long chain of rules would run out of stack
+    let mut visiting: Vec<(usize, usize)> = Vec::new();
+    for root in 0..after.len() {
+        if index[root].is_some() {
+            continue;
+        }
+        visiting.push((root, 0));
+        while let Some((v, followed)) = visiting.last_mut() {
+            let v = *v;
+            if *followed == 0 && index[v].is_none() {
+                index[v] = Some(next);
+                low[v] = next;
+                next += 1;
+                stack.push(v);
+                on_stack[v] = true;
+            }
+
+            if let Some(&w) = after[v].get(*followed) {
+                *followed += 1;
+                match index[w] {
+                    None => visiting.push((w, 0)),
+                    Some(index) if on_stack[w] => low[v] = low[v].min(index),
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            visiting.pop();
+            if let Some((parent, _)) = visiting.last() {
+                low[*parent] = low[*parent].min(low[v]);
+            }
+            if Some(low[v]) == index[v] {
+                let mut component = Vec::new();
+                loop {
+                    let w = stack.pop().unwrap();
+                    on_stack[w] = false;
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                if component.len() > 1 || after[v].contains(&v) {
+                    found.push(component);
+                }
+            }
+        }
+    }
+
+    found
+}
+
+// breadth first from each page in turn, staying inside the component, until the search gets back to where it started
+fn shortest_cycle(after: &[Vec<usize>], component: &[usize]) -> Vec<usize> {
+    let mut inside = vec![false; after.len()];
+    for v in component {
+        inside[*v] = true;
+    }
+
+    let mut best: Option<Vec<usize>> = None;
+    for &start in component {
+        let mut came_from = vec![None; after.len()];
+        let mut queue = VecDeque::from([start]);
+        'search: while let Some(v) = queue.pop_front() {
+            for &w in &after[v] {
+                if w == start {
+                    let mut cycle = vec![v];
+                    while let Some(previous) = came_from[*cycle.last().unwrap()] {
+                        cycle.push(previous);
+                    }
+                    cycle.reverse();
+                    if best.as_ref().is_none_or(|b| cycle.len() < b.len()) {
+                        best = Some(cycle);
+                    }
+                    break 'search;
+                }
+                if inside[w] && came_from[w].is_none() {
+                    came_from[w] = Some(v);
+                    queue.push_back(w);
+                }
+            }
+        }
+    }
+
+    best.unwrap()
+}
+
+fn cycles_in(pages: &[i64], after: &[Vec<usize>]) -> Vec<Cycle> {
+    let mut cycles = components(after)
+        .into_iter()
+        .map(|mut component| {
+            // so the evidence starts from the smallest page it can
+            component.sort_by_key(|v| pages[*v]);
+            let mut pages_in = component.iter().map(|v| pages[*v]).collect::<Vec<_>>();
+            pages_in.sort();
+            Cycle {
+                pages: pages_in,
+                evidence: shortest_cycle(after, &component).into_iter().map(|v| pages[v]).collect(),
+            }
+        })
+        .collect::<Vec<_>>();
+    cycles.sort_by(|a, b| a.pages.cmp(&b.pages));
+    cycles
+}
+
+impl UpdateGraph {
+    pub fn new(rules: &HashMap<i64, HashSet<i64>>, update: &[i64]) -> Result<UpdateGraph, OrderError> {
+        let mut index = HashMap::new();
+        for (i, page) in update.iter().enumerate() {
+            if index.insert(*page, i).is_some() {
+                return Err(OrderError::RepeatedPage(*page));
+            }
+        }
+
+        let after = update
+            .iter()
+            .map(|page| {
+                let mut later = rules
+                    .get(page)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|p| index.get(p).copied())
+                    .collect::<Vec<_>>();
+                later.sort();
+                later
+            })
+            .collect();
+
+        Ok(UpdateGraph {
+            pages: update.to_vec(),
+            after,
+        })
+    }
+
+    pub fn pages(&self) -> &[i64] {
+        &self.pages
+    }
+
+    // every rule points forwards through the update
+    pub fn is_ordered(&self) -> bool {
+        self.after
+            .iter()
+            .enumerate()
+            .all(|(i, later)| later.iter().all(|j| i < *j))
+    }
+
+    fn reaches(&self, from: usize, to: usize) -> bool {
+        let mut seen = vec![false; self.pages.len()];
+        let mut stack = vec![from];
+        while let Some(i) = stack.pop() {
+            if i == to {
+                return true;
+            }
+            for &j in &self.after[i] {
+                if !seen[j] {
+                    seen[j] = true;
+                    stack.push(j);
+                }
+            }
+        }
+
+        false
+    }
+
+    // follows chains of rules, so 47|53 and 53|29 put 47 before 29 even without a 47|29 rule;
+    // None when the pages aren't both in the update or nothing relates them
+    pub fn compare(&self, a: i64, b: i64) -> Option<Ordering> {
+        let a = self.pages.iter().position(|p| *p == a)?;
+        let b = self.pages.iter().position(|p| *p == b)?;
+        if a == b {
+            Some(Ordering::Equal)
+        } else if self.reaches(a, b) {
+            Some(Ordering::Less)
+        } else if self.reaches(b, a) {
+            Some(Ordering::Greater)
+        } else {
+            None
+        }
+    }
+
+    pub fn cycles(&self) -> Vec<Cycle> {
+        cycles_in(&self.pages, &self.after)
+    }
+
+    // Kahn's algorithm; when there's a choice, it takes whichever page the update listed first
+    pub fn sort(&self) -> Result<Sorted, OrderError> {
+        let mut incoming = vec![0; self.pages.len()];
+        for later in &self.after {
+            for j in later {
+                incoming[*j] += 1;
+            }
+        }
+
+        let mut ready = (0..self.pages.len())
+            .filter(|i| incoming[*i] == 0)
+            .map(Reverse)
+            .collect::<BinaryHeap<_>>();
+        let mut order = Vec::with_capacity(self.pages.len());
+        let mut unrelated = None;
+        while let Some(Reverse(i)) = ready.pop() {
+            // both were free to go next, so neither can be before the other
+            if unrelated.is_none()
+                && let Some(Reverse(j)) = ready.peek()
+            {
+                unrelated = Some((self.pages[i], self.pages[*j]));
+            }
+            order.push(self.pages[i]);
+            for &j in &self.after[i] {
+                incoming[j] -= 1;
+                if incoming[j] == 0 {
+                    ready.push(Reverse(j));
+                }
+            }
+        }
+
+        // some pages never became free, which only happens when there's a cycle
+        if order.len() < self.pages.len() {
+            let cycle = self.cycles().swap_remove(0);
+            return Err(OrderError::Cyclic(cycle));
+        }
+
+        Ok(Sorted { order, unrelated })
+    }
+
+    // reach[i][j] when a chain of rules puts pages[i] somewhere before pages[j]
+    fn closure(&self) -> Vec<Vec<bool>> {
+        (0..self.pages.len())
+            .map(|i| {
+                let mut reach = vec![false; self.pages.len()];
+                let mut stack = vec![i];
+                while let Some(v) = stack.pop() {
+                    for &w in &self.after[v] {
+                        if !reach[w] {
+                            reach[w] = true;
+                            stack.push(w);
+                        }
+                    }
+                }
+                reach
+            })
+            .collect()
+    }
+
+    // the fewest pages to pick up and put back down so the update follows the rules.
+    // two pages clash when the update lists them the wrong way round (counting chains of
+    // rules), and clashing is transitive, so it's a partial order: the biggest set of pages
+    // that can all stay put is its largest antichain, found through a maximum matching
+    pub fn repair(&self) -> Result<Repair, OrderError> {
+        let n = self.pages.len();
+        let reach = self.closure();
+        if let Some(i) = (0..n).find(|i| reach[*i][*i]) {
+            let cycle = self.cycles().into_iter().find(|c| c.pages.contains(&self.pages[i])).unwrap();
+            return Err(OrderError::Cyclic(cycle));
+        }
+        let clashes = |i: usize, j: usize| i < j && reach[j][i];
+
+        // looks for a path from `root` to a free page on the right, kept by hand rather than
+        // recursing since it can be as long as the update
+        fn augment(
+            root: usize,
+            clashes: &impl Fn(usize, usize) -> bool,
+            seen: &mut [bool],
+            partner: &mut [Option<usize>],
+        ) -> bool {
+            // each left page on the path so far, with the next right page it should try
+            let mut path = vec![(root, 0)];
+            // the right page each step of the path goes through
+            let mut through = Vec::new();
+            while let Some((u, next)) = path.last_mut() {
+                let u = *u;
+                let Some(v) = (*next..partner.len()).find(|v| clashes(u, *v) && !seen[*v]) else {
+                    // a dead end, so back up and try the previous page's next option
+                    path.pop();
+                    through.pop();
+                    continue;
+                };
+                *next = v + 1;
+                seen[v] = true;
+                through.push(v);
+                match partner[v] {
+                    Some(w) => path.push((w, 0)),
+                    None => {
+                        for (&(u, _), &v) in path.iter().zip(&through) {
+                            partner[v] = Some(u);
+                        }
+                        return true;
+                    }
+                }
+            }
+            false
+        }
+
+        // partner[v] is the page matched on the left with v on the right
+        let mut partner = vec![None; n];
+        for u in 0..n {
+            augment(u, &clashes, &mut vec![false; n], &mut partner);
+        }
+
+        // König: alternate out from the unmatched left pages; whatever is reached on the left
+        // but not on the right is outside the minimum vertex cover on both sides
+        let mut left = vec![false; n];
+        let mut right = vec![false; n];
+        let mut queue = (0..n)
+            .filter(|u| !partner.contains(&Some(*u)))
+            .collect::<VecDeque<_>>();
+        for u in &queue {
+            left[*u] = true;
+        }
+        while let Some(u) = queue.pop_front() {
+            for v in 0..n {
+                if clashes(u, v) && !right[v] && partner[v] != Some(u) {
+                    right[v] = true;
+                    if let Some(w) = partner[v]
+                        && !left[w]
+                    {
+                        left[w] = true;
+                        queue.push_back(w);
+                    }
+                }
+            }
+        }
+        let kept = (0..n).filter(|i| left[*i] && !right[*i]).collect::<Vec<_>>();
+
+        // the pages that stay put have to keep their order, and everything else fits around them
+        let mut chained = self.clone();
+        for pair in kept.windows(2) {
+            chained.after[pair[0]].push(pair[1]);
+        }
+        let order = chained.sort()?.order;
+
+        let mut moves = Vec::new();
+        for (position, page) in order.iter().enumerate() {
+            let index = self.pages.iter().position(|p| p == page).unwrap();
+            if kept.binary_search(&index).is_err() {
+                moves.push(Move {
+                    page: *page,
+                    after: position.checked_sub(1).map(|p| order[p]),
+                });
+            }
+        }
+
+        Ok(Repair {
+            kept: kept.into_iter().map(|i| self.pages[i]).collect(),
+            moves,
+            order,
+        })
+    }
+}
+
+// take a page out and put it back just after another one, or at the front
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub page: i64,
+    pub after: Option<i64>,
+}
+
+impl Move {
+    pub fn apply(&self, pages: &mut Vec<i64>) {
+        pages.retain(|p| *p != self.page);
+        let at = match self.after {
+            Some(after) => pages.iter().position(|p| *p == after).unwrap() + 1,
+            None => 0,
+        };
+        pages.insert(at, self.page);
+    }
+}
+
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.after {
+            Some(after) => write!(f, "move {} after {}", self.page, after),
+            None => write!(f, "move {} to the front", self.page),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Repair {
+    // the pages that don't move, in the order the update already has them
+    pub kept: Vec<i64>,
+    // applied in this order
+    pub moves: Vec<Move>,
+    pub order: Vec<i64>,
+}
+
+impl Repair {
+    // how much of the update has to move: 0 when it's already fine, getting close to 1
+    // when hardly anything can stay where it is
+    pub fn disorder(&self) -> f64 {
+        // an empty update has nothing out of place
+        if self.order.is_empty() {
+            return 0.0;
+        }
+        self.moves.len() as f64 / self.order.len() as f64
+    }
+}
+
+impl Input {
+    pub fn updates(&self) -> &[Vec<i64>] {
+        &self.updates
+    }
+
+    pub fn graph(&self, update: &[i64]) -> Result<UpdateGraph, OrderError> {
+        UpdateGraph::new(&self.rules, update)
+    }
+
+    // the misordered updates that can be fixed, worst first
+    pub fn triage(&self) -> Vec<(usize, Repair)> {
+        let mut repairs = self
+            .updates
+            .iter()
+            .enumerate()
+            .filter_map(|(update, pages)| {
+                let repair = self.graph(pages).and_then(|g| g.repair()).ok()?;
+                (!repair.moves.is_empty()).then_some((update, repair))
+            })
+            .collect::<Vec<_>>();
+        repairs.sort_by(|(a, a_repair), (b, b_repair)| {
+            b_repair
+                .disorder()
+                .total_cmp(&a_repair.disorder())
+                .then(a.cmp(b))
+        });
+        repairs
+    }
+
+    // cycles anywhere in the rule set, whether or not an update runs into them
+    pub fn cycles(&self) -> Vec<Cycle> {
+        let mut pages = self
+            .rules
+            .iter()
+            .flat_map(|(page, later)| std::iter::once(page).chain(later))
+            .copied()
+            .collect::<Vec<_>>();
+        pages.sort();
+        pages.dedup();
+        let after = pages
+            .iter()
+            .map(|page| {
+                let mut later = self
+                    .rules
+                    .get(page)
+                    .into_iter()
+                    .flatten()
+                    .map(|p| pages.binary_search(p).unwrap())
+                    .collect::<Vec<_>>();
+                later.sort();
+                later
+            })
+            .collect::<Vec<_>>();
+
+        cycles_in(&pages, &after)
+    }
+
+    pub fn validate(&self) -> Validation {
+        let cycles = self.cycles();
+        let updates = self
+            .updates
+            .iter()
+            .enumerate()
+            .filter_map(|(update, pages)| {
+                let touches = pages
+                    .iter()
+                    .filter(|p| cycles.iter().any(|c| c.pages.binary_search(p).is_ok()))
+                    .copied()
+                    .collect::<Vec<_>>();
+                if touches.is_empty() {
+                    return None;
+                }
+
+                // a repeated page doesn't change which rules apply
+                let mut unique = pages.clone();
+                unique.sort();
+                unique.dedup();
+                let cycles = self.graph(&unique).unwrap().cycles();
+                Some(UpdateDiagnostic { update, touches, cycles })
+            })
+            .collect();
+
+        Validation { cycles, updates }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UpdateDiagnostic {
+    // index into the updates
+    pub update: usize,
+    // the update's pages that are part of a cycle somewhere in the rule set
+    pub touches: Vec<i64>,
+    // cycles among the update's own pages; the update can't be ordered while there are any
+    pub cycles: Vec<Cycle>,
+}
+
+impl UpdateDiagnostic {
+    pub fn is_orderable(&self) -> bool {
+        self.cycles.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Validation {
+    pub cycles: Vec<Cycle>,
+    // only the updates that touch one of the cycles
+    pub updates: Vec<UpdateDiagnostic>,
+}
+
+impl Validation {
+    pub fn is_consistent(&self) -> bool {
+        self.cycles.is_empty()
+    }
+
+    pub fn unorderable(&self) -> impl Iterator<Item = usize> + '_ {
+        self.updates.iter().filter(|u| !u.is_orderable()).map(|u| u.update)
+    }
+}
+
+impl std::fmt::Display for Validation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_consistent() {
+            return writeln!(f, "the rules are consistent");
+        }
+
+        for cycle in &self.cycles {
+            writeln!(f, "cycle among pages {:?}: {}", cycle.pages, cycle)?;
+        }
+        for update in &self.updates {
+            write!(f, "update {} touches pages {:?}", update.update + 1, update.touches)?;
+            match update.cycles.first() {
+                Some(cycle) => writeln!(f, " and can't be ordered: {}", cycle)?,
+                None => writeln!(f, " but can still be ordered")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn part_1(input: &Input) -> i64 {
+    input
+        .updates
+        .iter()
+        // an empty update is in order, but has no middle page
+        .filter(|u| !u.is_empty() && input.graph(u).is_ok_and(|g| g.is_ordered()))
+        .map(|u| u[u.len() / 2])
+        .sum()
+}
+
+// updates whose rules form a cycle have no correct order, so there's nothing to fix
+pub fn part_2(input: &Input) -> i64 {
+    input
+        .updates
+        .iter()
+        .filter_map(|u| {
+            let graph = input.graph(u).ok()?;
+            if graph.is_ordered() {
+                return None;
+            }
+            graph.sort().ok().map(|sorted| sorted.middle())
+        })
+        .sum()
+}
+
+// the same answer, but fixing each update with as few moves as possible rather than re-sorting it
+pub fn part_2_repair(input: &Input) -> i64 {
+    input
+        .triage()
+        .iter()
+        .map(|(_, repair)| repair.order[repair.order.len() / 2])
+        .sum()
+}
+
+pub use common::ParseError;
+
+pub fn try_parse_input(input: &str) -> Result<Input, ParseError> {
+    let Some((rules_part, updates_part)) = input.split_once("\n\n") else {
+        return Err(ParseError("expected a blank line between the rules and the updates".to_string()));
+    };
+    let number = |line: usize, n: &str| {
+        n.parse::<i64>()
+            .map_err(|_| ParseError(format!("line {}: {:?} isn't a page number", line, n)))
+    };
+
+    let mut rules: HashMap<i64, HashSet<i64>> = HashMap::new();
+    for (line, rule) in rules_part.lines().enumerate() {
+        let Some((first, second)) = rule.split_once("|") else {
+            return Err(ParseError(format!("line {}: rules look like 47|53", line + 1)));
+        };
+        rules
+            .entry(number(line + 1, first)?)
+            .or_default()
+            .insert(number(line + 1, second)?);
+    }
+
+    let first_update_line = rules_part.lines().count() + 2;
+    let updates = updates_part
+        .lines()
+        .enumerate()
+        .map(|(line, update)| {
+            update
+                .split(",")
+                .map(|num| number(first_update_line + line, num))
+                .collect()
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Input { rules, updates })
+}
+
+pub fn parse_input(input: &str) -> Input {
+    try_parse_input(input).unwrap()
+}
+
+#[test]
+pub fn test() {
+    let input = r#"47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+"#;
+
+    let input = parse_input(input);
+    assert_eq!(part_1(&input), 143);
+    assert_eq!(part_2(&input), 123);
+}
+
+#[test]
+pub fn test_sort() {
+    let input = parse_input("1|2\n2|3\n4|5\n5|4\n\n3,1,2\n3,1\n2,1,9\n4,5,6\n1,2,1\n");
+    let graph = input.graph(&input.updates()[0]).unwrap();
+    assert!(!graph.is_ordered());
+    // there's no 1|3 rule, but 2 sits between them
+    assert_eq!(graph.compare(1, 3), Some(Ordering::Less));
+    assert_eq!(graph.compare(3, 1), Some(Ordering::Greater));
+    assert_eq!(graph.compare(1, 7), None);
+    let sorted = graph.sort().unwrap();
+    assert_eq!(sorted.order, vec![1, 2, 3]);
+    assert!(sorted.is_determined());
+    assert_eq!(sorted.middle(), 2);
+
+    // 2 isn't in this update, so nothing relates 3 and 1
+    let graph = input.graph(&input.updates()[1]).unwrap();
+    assert!(graph.is_ordered());
+    assert_eq!(graph.compare(3, 1), None);
+    assert_eq!(graph.sort().unwrap(), Sorted { order: vec![3, 1], unrelated: Some((3, 1)) });
+
+    let sorted = input.graph(&input.updates()[2]).unwrap().sort().unwrap();
+    assert_eq!(sorted.order, vec![1, 2, 9]);
+    assert_eq!(sorted.unrelated, Some((1, 9)));
+
+    let graph = input.graph(&input.updates()[3]).unwrap();
+    assert_eq!(
+        graph.sort(),
+        Err(OrderError::Cyclic(Cycle { pages: vec![4, 5], evidence: vec![4, 5] }))
+    );
+    assert_eq!(input.graph(&input.updates()[4]), Err(OrderError::RepeatedPage(1)));
+
+    // the cyclic and repeated updates are never counted
+    assert_eq!(part_1(&input), 1);
+    assert_eq!(part_2(&input), 2 + 2);
+}
+
+#[test]
+pub fn test_cycles() {
+    // 1 -> 2 -> 3 -> 1 with a shortcut 1 -> 3 -> 1 through 3|1, plus a page with a rule about itself
+    let input = parse_input("1|2\n2|3\n3|1\n1|3\n3|4\n7|7\n\n1,2\n3,2,1,4\n4,5\n7,8,7\n");
+    assert_eq!(input.cycles(), vec![
+        Cycle { pages: vec![1, 2, 3], evidence: vec![1, 3] },
+        Cycle { pages: vec![7], evidence: vec![7] },
+    ]);
+
+    let validation = input.validate();
+    assert!(!validation.is_consistent());
+    // 1 and 2 are both in the big cycle, but without 3 the update has no loop of its own
+    assert_eq!(validation.updates[0], UpdateDiagnostic { update: 0, touches: vec![1, 2], cycles: vec![] });
+    assert_eq!(validation.updates[1].touches, vec![3, 2, 1]);
+    assert_eq!(validation.updates[1].cycles[0].evidence, vec![1, 3]);
+    assert_eq!(validation.updates[2].update, 3);
+    assert_eq!(validation.unorderable().collect::<Vec<_>>(), vec![1, 3]);
+    assert_eq!(validation.to_string(), "cycle among pages [1, 2, 3]: 1 -> 3 -> 1
+cycle among pages [7]: 7 -> 7
+update 1 touches pages [1, 2] but can still be ordered
+update 2 touches pages [3, 2, 1] and can't be ordered: 1 -> 3 -> 1
+update 4 touches pages [7, 7] and can't be ordered: 7 -> 7
+");
+
+    let Err(OrderError::Cyclic(cycle)) = input.graph(&[4, 3, 2, 1]).unwrap().sort() else {
+        panic!("expected a cycle");
+    };
+    assert_eq!(cycle.to_string(), "1 -> 3 -> 1");
+    assert!(parse_input("1|2\n\n1,2\n").validate().is_consistent());
+
+    // far longer a chain of rules than there'd be stack to follow it with
+    let chain = (0..100_000).map(|page| format!("{}|{}\n", page, page + 1)).collect::<String>();
+    let input = parse_input(&format!("{}100000|100000\n\n1,2\n", chain));
+    assert_eq!(input.cycles(), vec![Cycle { pages: vec![100000], evidence: vec![100000] }]);
+}
+
+#[test]
+pub fn test_repair() {
+    let input = parse_input(r#"47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+"#);
+
+    let repair = input.graph(&input.updates()[3]).unwrap().repair().unwrap();
+    // moving 97 to the front would do just as well
+    assert_eq!(repair.kept, vec![97, 47, 61, 53]);
+    assert_eq!(repair.moves, vec![Move { page: 75, after: Some(97) }]);
+    assert_eq!(repair.order, vec![97, 75, 47, 61, 53]);
+    assert_eq!(repair.moves[0].to_string(), "move 75 after 97");
+    assert_eq!(Move { page: 97, after: None }.to_string(), "move 97 to the front");
+    assert_eq!(repair.disorder(), 0.2);
+    assert_eq!(input.graph(&[]).unwrap().repair().unwrap().disorder(), 0.0);
+
+    let repair = input.graph(&input.updates()[5]).unwrap().repair().unwrap();
+    assert_eq!(repair.moves.len(), 2);
+    assert_eq!(repair.order, vec![97, 75, 47, 29, 13]);
+
+    let triage = input.triage();
+    assert_eq!(triage.iter().map(|(u, _)| *u).collect::<Vec<_>>(), vec![5, 4, 3]);
+    assert_eq!(part_2_repair(&input), part_2(&input));
+
+    // 3 has to come after 1 because of 2, so 3 and 1 can't both stay where they are
+    let input = parse_input("1|2\n2|3\n5|4\n\n3,4,1,2,5\n");
+    let repair = input.graph(&input.updates()[0]).unwrap().repair().unwrap();
+    assert_eq!(repair.moves.len(), 2);
+    assert!(input.graph(&repair.order).unwrap().is_ordered());
+    assert_eq!(input.graph(&[4, 5, 6]).unwrap().repair().unwrap().moves.len(), 1);
+    assert!(parse_input("1|2\n2|1\n\n1,2\n").graph(&[1, 2]).unwrap().repair().is_err());
+
+    // against trying every set of pages to leave in place
+    let input = parse_input("1|3\n2|4\n3|5\n1|6\n4|6\n2|7\n5|7\n\n1,2\n");
+    let pages = [5, 3, 7, 1, 6, 2, 4];
+    for len in 1..=pages.len() {
+        for start in 0..=pages.len() - len {
+            let update = &pages[start..start + len];
+            let graph = input.graph(update).unwrap();
+            let repair = graph.repair().unwrap();
+            let most_kept = (0..1u32 << len)
+                .filter(|mask| {
+                    let kept = (0..len).filter(|i| mask & (1 << i) != 0).collect::<Vec<_>>();
+                    let chained = kept.windows(2).fold(graph.clone(), |mut g, pair| {
+                        g.after[pair[0]].push(pair[1]);
+                        g
+                    });
+                    chained.sort().is_ok()
+                })
+                .map(|mask| mask.count_ones() as usize)
+                .max()
+                .unwrap();
+            assert_eq!(repair.kept.len(), most_kept, "{:?}", update);
+
+            let mut moved = update.to_vec();
+            for m in &repair.moves {
+                m.apply(&mut moved);
+            }
+            assert_eq!(moved, repair.order, "{:?}", update);
+            assert!(input.graph(&moved).unwrap().is_ordered());
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+pub fn test_json_round_trip() {
+    let input = r#"47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+"#;
+
+    let input = parse_input(input);
+    let json = serde_json::to_string(&input).unwrap();
+    let reloaded: Input = serde_json::from_str(&json).unwrap();
+    assert_eq!(part_1(&reloaded), part_1(&input));
+    assert_eq!(part_2(&reloaded), part_2(&input));
+
+    // an update with no pages couldn't have come from the parser
+    assert!(serde_json::from_str::<Input>(r#"{"rules":{},"updates":[[1],[]]}"#).is_err());
+    let empty = Input { rules: HashMap::new(), updates: vec![vec![]] };
+    assert_eq!(part_1(&empty), 0);
+}
@@ -0,0 +1,1565 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Coord {
+    x: usize,
+    y: usize,
+}
+
+impl From<Coord> for (usize, usize) {
+    fn from(coord: Coord) -> Self {
+        (coord.x, coord.y)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Orientation {
+    Up,
+    Left,
+    Right,
+    Down,
+}
+
+impl Orientation {
+    fn index(self) -> usize {
+        match self {
+            Orientation::Up => 0,
+            Orientation::Left => 1,
+            Orientation::Right => 2,
+            Orientation::Down => 3,
+        }
+    }
+
+    pub fn turn_left(self) -> Orientation {
+        self.turn_right().turn_right().turn_right()
+    }
+
+    pub fn turn_around(self) -> Orientation {
+        self.turn_right().turn_right()
+    }
+
+    pub fn turn_right(self) -> Orientation {
+        match self {
+            Orientation::Up => Orientation::Right,
+            Orientation::Left => Orientation::Up,
+            Orientation::Right => Orientation::Down,
+            Orientation::Down => Orientation::Left,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Area {
+    obstructions_by_x: HashMap<usize, BTreeSet<usize>>,
+    obstructions_by_y: HashMap<usize, BTreeSet<usize>>,
+    size: Coord,
+}
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Guard {
+    location: Coord,
+    orientation: Orientation,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedInput"))]
+pub struct Input {
+    area: Area,
+    // the first guard on the map, which is the one the puzzle's about
+    guard: Guard,
+    #[cfg_attr(feature = "serde", serde(default))]
+    others: Vec<Guard>,
+}
+
+// a map loaded from json: the two lists of obstructions have to agree, and everything has to be
+// on the map, with no guard standing on an obstruction
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedInput {
+    area: Area,
+    guard: Guard,
+    #[serde(default)]
+    others: Vec<Guard>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedInput> for Input {
+    type Error = ParseError;
+
+    fn try_from(unchecked: UncheckedInput) -> Result<Self, ParseError> {
+        let area = &unchecked.area;
+        let count =
+            |by: &HashMap<usize, BTreeSet<usize>>| by.values().map(BTreeSet::len).sum::<usize>();
+        if count(&area.obstructions_by_x) != count(&area.obstructions_by_y) {
+            return Err(ParseError(
+                "the obstructions by row and by column don't match".to_string(),
+            ));
+        }
+        for (x, ys) in &area.obstructions_by_x {
+            for y in ys {
+                if *x > area.size.x || *y > area.size.y {
+                    return Err(ParseError(format!(
+                        "the obstruction at ({}, {}) is off the map",
+                        x, y
+                    )));
+                }
+                if !area
+                    .obstructions_by_y
+                    .get(y)
+                    .is_some_and(|xs| xs.contains(x))
+                {
+                    return Err(ParseError(
+                        "the obstructions by row and by column don't match".to_string(),
+                    ));
+                }
+            }
+        }
+
+        let input = Input {
+            area: unchecked.area,
+            guard: unchecked.guard,
+            others: unchecked.others,
+        };
+        for (location, _) in input.guards() {
+            let Coord { x, y } = location;
+            if x > input.area.size.x || y > input.area.size.y {
+                return Err(ParseError(format!(
+                    "the guard at ({}, {}) is off the map",
+                    x, y
+                )));
+            }
+            if input.is_obstruction(location) {
+                return Err(ParseError(format!(
+                    "the guard at ({}, {}) is standing on an obstruction",
+                    x, y
+                )));
+            }
+        }
+
+        Ok(input)
+    }
+}
+
+pub use common::ParseError;
+
+pub fn try_parse_input(input: &str) -> Result<Input, ParseError> {
+    let mut size = Coord { x: 0, y: 0 };
+    let mut guards = Vec::new();
+    let mut obstructions_by_x = HashMap::new();
+    let mut obstructions_by_y = HashMap::new();
+    for (y, line) in input.lines().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            let location = Coord { x, y };
+            size = location;
+            match c {
+                '#' => {
+                    obstructions_by_x
+                        .entry(x)
+                        .or_insert(BTreeSet::new())
+                        .insert(y);
+                    obstructions_by_y
+                        .entry(y)
+                        .or_insert(BTreeSet::new())
+                        .insert(x);
+                }
+                '^' | '<' | '>' | 'v' => {
+                    guards.push(Guard {
+                        location,
+                        orientation: match c {
+                            '^' => Orientation::Up,
+                            '<' => Orientation::Left,
+                            '>' => Orientation::Right,
+                            _ => Orientation::Down,
+                        },
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+    let mut guards = guards.into_iter();
+    let Some(guard) = guards.next() else {
+        return Err(ParseError("there's no guard on the map".to_string()));
+    };
+
+    Ok(Input {
+        area: Area {
+            obstructions_by_x,
+            obstructions_by_y,
+            size,
+        },
+        guard,
+        others: guards.collect(),
+    })
+}
+
+pub fn parse_input(input: &str) -> Input {
+    try_parse_input(input).unwrap()
+}
+
+impl Input {
+    // returns where the guard stops (if that is in the grid)
+    pub fn next_obstruction_for_guard(&self) -> Option<Coord> {
+        self.next_obstruction(self.guard.location, self.guard.orientation)
+    }
+
+    // the same for a guard anywhere
+    pub fn next_obstruction(&self, location: Coord, orientation: Orientation) -> Option<Coord> {
+        match orientation {
+            Orientation::Up => self
+                .area
+                .obstructions_by_x
+                .get(&location.x)
+                .and_then(|os| os.range(..location.y).next_back())
+                .map(|y| Coord {
+                    x: location.x,
+                    y: *y,
+                }),
+            Orientation::Left => self
+                .area
+                .obstructions_by_y
+                .get(&location.y)
+                .and_then(|os| os.range(..location.x).next_back())
+                .map(|x| Coord {
+                    x: *x,
+                    y: location.y,
+                }),
+            Orientation::Right => self
+                .area
+                .obstructions_by_y
+                .get(&location.y)
+                .and_then(|os| os.range(location.x..).next())
+                .map(|x| Coord {
+                    x: *x,
+                    y: location.y,
+                }),
+            Orientation::Down => self
+                .area
+                .obstructions_by_x
+                .get(&location.x)
+                .and_then(|os| os.range(location.y..).next())
+                .map(|y| Coord {
+                    x: location.x,
+                    y: *y,
+                }),
+        }
+    }
+}
+
+// the slow way round - one cell (or one turn) at a time, for watching the guard walk
+impl Input {
+    pub fn guard(&self) -> (Coord, Orientation) {
+        (self.guard.location, self.guard.orientation)
+    }
+
+    // every guard on the map, starting with the puzzle's
+    pub fn guards(&self) -> Vec<(Coord, Orientation)> {
+        std::iter::once(&self.guard)
+            .chain(&self.others)
+            .map(|g| (g.location, g.orientation))
+            .collect()
+    }
+
+    pub fn is_obstruction(&self, Coord { x, y }: Coord) -> bool {
+        self.area
+            .obstructions_by_x
+            .get(&x)
+            .is_some_and(|ys| ys.contains(&y))
+    }
+
+    // just the first guard. None once it walks off the map
+    pub fn step(&self) -> Option<Input> {
+        let Coord { x, y } = self.guard.location;
+        let ahead = match self.guard.orientation {
+            Orientation::Up => Coord {
+                x,
+                y: y.checked_sub(1)?,
+            },
+            Orientation::Left => Coord {
+                x: x.checked_sub(1)?,
+                y,
+            },
+            Orientation::Right => Coord { x: x + 1, y },
+            Orientation::Down => Coord { x, y: y + 1 },
+        };
+        if ahead.x > self.area.size.x || ahead.y > self.area.size.y {
+            return None;
+        }
+
+        let mut next = self.clone();
+        if self.is_obstruction(ahead) {
+            next.guard.orientation = self.guard.orientation.turn_right();
+        } else {
+            next.guard.location = ahead;
+        }
+        Some(next)
+    }
+}
+
+impl std::fmt::Display for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render_with(|_| None))
+    }
+}
+
+// one guard from obstruction to obstruction, until it either walks off the map or turns at a
+// corner the same way it has before
+pub fn walk(
+    input: &Input,
+    (mut location, mut orientation): (Coord, Orientation),
+    policy: &dyn MovementPolicy,
+) -> GuardPath {
+    let start = (location, orientation);
+    let period = period(policy);
+    let mut cells = HashSet::from([location]);
+    let mut corners = HashSet::new();
+    let mut turns = 0;
+
+    while let Some(Coord { x, y }) = input.next_obstruction(location, orientation) {
+        match orientation {
+            Orientation::Up => {
+                cells.extend((y + 1..location.y).map(|y| Coord { x, y }));
+                location = Coord { x, y: y + 1 };
+            }
+            Orientation::Left => {
+                cells.extend((x + 1..location.x).map(|x| Coord { x, y }));
+                location = Coord { x: x + 1, y };
+            }
+            Orientation::Right => {
+                cells.extend((location.x..x).map(|x| Coord { x, y }));
+                location = Coord { x: x - 1, y };
+            }
+            Orientation::Down => {
+                cells.extend((location.y..y).map(|y| Coord { x, y }));
+                location = Coord { x, y: y - 1 };
+            }
+        }
+
+        if !corners.insert((location, orientation, turns)) {
+            return GuardPath {
+                start,
+                cells,
+                outcome: Outcome::Looped,
+            };
+        }
+        orientation = policy.turn(orientation, turns);
+        turns = (turns + 1) % period;
+    }
+
+    // now add the cells that take the guard off the board
+    let Coord { x, y } = location;
+    match orientation {
+        Orientation::Up => cells.extend((0..y).map(|y| Coord { x, y })),
+        Orientation::Left => cells.extend((0..x).map(|x| Coord { x, y })),
+        Orientation::Right => cells.extend((x..=input.area.size.x).map(|x| Coord { x, y })),
+        Orientation::Down => cells.extend((y..=input.area.size.y).map(|y| Coord { x, y })),
+    }
+
+    GuardPath {
+        start,
+        cells,
+        outcome: Outcome::Exited,
+    }
+}
+
+// where walking in a straight line from each cell, in each direction, ends up: the cell in
+// front of an obstruction, or off the map. part 2 only ever adds one obstruction, which is
+// cheap to check for on the way rather than rebuilding the table
+#[derive(Clone, Debug)]
+pub struct JumpTable {
+    width: usize,
+    height: usize,
+    obstructed: Vec<bool>,
+    // indexed by cell then orientation
+    stops: Vec<Option<Coord>>,
+}
+
+impl JumpTable {
+    pub fn new(input: &Input) -> JumpTable {
+        let width = input.area.size.x + 1;
+        let height = input.area.size.y + 1;
+        let mut obstructed = vec![false; width * height];
+        for (x, ys) in &input.area.obstructions_by_x {
+            for y in ys {
+                if *x < width && *y < height {
+                    obstructed[y * width + x] = true;
+                }
+            }
+        }
+
+        let mut table = JumpTable {
+            width,
+            height,
+            obstructed,
+            stops: vec![None; width * height * 4],
+        };
+        // sweep each row and column in both directions, remembering the last obstruction passed
+        for y in 0..height {
+            let mut stop = None;
+            for x in 0..width {
+                if table.obstructed[y * width + x] {
+                    stop = Some(Coord { x: x + 1, y });
+                }
+                let slot = table.slot(Coord { x, y }, Orientation::Left);
+                table.stops[slot] = stop;
+            }
+            let mut stop = None;
+            for x in (0..width).rev() {
+                if table.obstructed[y * width + x] {
+                    stop = x.checked_sub(1).map(|x| Coord { x, y });
+                }
+                let slot = table.slot(Coord { x, y }, Orientation::Right);
+                table.stops[slot] = stop;
+            }
+        }
+        for x in 0..width {
+            let mut stop = None;
+            for y in 0..height {
+                if table.obstructed[y * width + x] {
+                    stop = Some(Coord { x, y: y + 1 });
+                }
+                let slot = table.slot(Coord { x, y }, Orientation::Up);
+                table.stops[slot] = stop;
+            }
+            let mut stop = None;
+            for y in (0..height).rev() {
+                if table.obstructed[y * width + x] {
+                    stop = y.checked_sub(1).map(|y| Coord { x, y });
+                }
+                let slot = table.slot(Coord { x, y }, Orientation::Down);
+                table.stops[slot] = stop;
+            }
+        }
+
+        table
+    }
+
+    fn slot(&self, Coord { x, y }: Coord, orientation: Orientation) -> usize {
+        (y * self.width + x) * 4 + orientation.index()
+    }
+
+    fn contains(&self, Coord { x, y }: Coord) -> bool {
+        x < self.width && y < self.height
+    }
+
+    // where the guard stops walking from `from`, with `extra` as one more obstruction if there is one
+    pub fn jump(
+        &self,
+        from: Coord,
+        orientation: Orientation,
+        extra: Option<Coord>,
+    ) -> Option<Coord> {
+        let stop = self.stops[self.slot(from, orientation)];
+        let Some(extra) = extra else {
+            return stop;
+        };
+
+        // the extra obstruction only matters if it's between here and where the guard would have stopped
+        match orientation {
+            Orientation::Up
+                if extra.x == from.x && extra.y < from.y && stop.is_none_or(|s| extra.y >= s.y) =>
+            {
+                Some(Coord {
+                    x: from.x,
+                    y: extra.y + 1,
+                })
+            }
+            Orientation::Left
+                if extra.y == from.y && extra.x < from.x && stop.is_none_or(|s| extra.x >= s.x) =>
+            {
+                Some(Coord {
+                    x: extra.x + 1,
+                    y: from.y,
+                })
+            }
+            Orientation::Right
+                if extra.y == from.y && extra.x > from.x && stop.is_none_or(|s| extra.x <= s.x) =>
+            {
+                Some(Coord {
+                    x: extra.x - 1,
+                    y: from.y,
+                })
+            }
+            Orientation::Down
+                if extra.x == from.x && extra.y > from.y && stop.is_none_or(|s| extra.y <= s.y) =>
+            {
+                Some(Coord {
+                    x: from.x,
+                    y: extra.y - 1,
+                })
+            }
+            _ => stop,
+        }
+    }
+
+    // somewhere to keep track of every (cell, orientation, turns) state the policy can be in
+    fn states(&self, policy: &dyn MovementPolicy) -> usize {
+        self.stops.len() * period(policy)
+    }
+
+    fn state(
+        &self,
+        location: Coord,
+        orientation: Orientation,
+        turns: usize,
+        period: usize,
+    ) -> usize {
+        self.slot(location, orientation) * period + turns
+    }
+
+    // `seen` holds the generation each state was last reached in, so it doesn't have to be
+    // cleared between checks
+    fn loops_from(
+        &self,
+        (mut location, mut orientation, mut turns): (Coord, Orientation, usize),
+        extra: Option<Coord>,
+        policy: &dyn MovementPolicy,
+        seen: &mut [usize],
+        generation: usize,
+    ) -> bool {
+        let period = period(policy);
+        while let Some(stop) = self.jump(location, orientation, extra) {
+            location = stop;
+            let state = self.state(location, orientation, turns, period);
+            if seen[state] == generation {
+                return true;
+            }
+            seen[state] = generation;
+            orientation = policy.turn(orientation, turns);
+            turns = (turns + 1) % period;
+        }
+
+        false
+    }
+
+    pub fn enters_loop(
+        &self,
+        (location, orientation): (Coord, Orientation),
+        extra: Option<Coord>,
+        policy: &dyn MovementPolicy,
+    ) -> bool {
+        self.contains(location)
+            && self.loops_from(
+                (location, orientation, 0),
+                extra,
+                policy,
+                &mut vec![0; self.states(policy)],
+                1,
+            )
+    }
+
+    fn ahead(&self, Coord { x, y }: Coord, orientation: Orientation) -> Option<Coord> {
+        let ahead = match orientation {
+            Orientation::Up => Coord {
+                x,
+                y: y.checked_sub(1)?,
+            },
+            Orientation::Left => Coord {
+                x: x.checked_sub(1)?,
+                y,
+            },
+            Orientation::Right => Coord { x: x + 1, y },
+            Orientation::Down => Coord { x, y: y + 1 },
+        };
+        self.contains(ahead).then_some(ahead)
+    }
+
+    // the guard's state just before it first steps onto each cell of its path, in the order
+    // it gets there; putting an obstruction on that cell changes nothing before this point
+    pub fn first_visits(
+        &self,
+        (mut location, mut orientation): (Coord, Orientation),
+        policy: &dyn MovementPolicy,
+    ) -> Vec<FirstVisit> {
+        let mut visits = Vec::new();
+        if !self.contains(location) {
+            return visits;
+        }
+        let period = period(policy);
+        let mut visited = vec![false; self.width * self.height];
+        visited[location.y * self.width + location.x] = true;
+        // in case the guard is already stuck in a loop
+        let mut states = vec![false; self.states(policy)];
+
+        let mut step = 0;
+        let mut turns = 0;
+        while !std::mem::replace(
+            &mut states[self.state(location, orientation, turns, period)],
+            true,
+        ) {
+            let Some(ahead) = self.ahead(location, orientation) else {
+                break;
+            };
+
+            if self.obstructed[ahead.y * self.width + ahead.x] {
+                orientation = policy.turn(orientation, turns);
+                turns = (turns + 1) % period;
+            } else {
+                if !std::mem::replace(&mut visited[ahead.y * self.width + ahead.x], true) {
+                    visits.push(FirstVisit {
+                        cell: ahead,
+                        location,
+                        orientation,
+                        turns,
+                        step,
+                    });
+                }
+                location = ahead;
+            }
+            step += 1;
+        }
+
+        visits
+    }
+
+    // one step at a time from the start with one more obstruction, until the guard either leaves
+    // or comes back to a state it's been in. it has to be from the start rather than the first
+    // visit, since the guard can already be on what turns into the loop before it gets there
+    pub fn trace_loop(
+        &self,
+        (mut location, mut orientation): (Coord, Orientation),
+        obstruction: Coord,
+        policy: &dyn MovementPolicy,
+    ) -> Option<LoopReport> {
+        // the guard's standing there
+        if !self.contains(location) || obstruction == location {
+            return None;
+        }
+        let period = period(policy);
+        let mut step = 0;
+        let mut turns = 0;
+        let mut reached = vec![None; self.states(policy)];
+        let mut corners = Vec::new();
+        let entered_at = loop {
+            if let Some(at) =
+                reached[self.state(location, orientation, turns, period)].replace(step)
+            {
+                break at;
+            }
+
+            let ahead = self.ahead(location, orientation)?;
+            if ahead == obstruction || self.obstructed[ahead.y * self.width + ahead.x] {
+                corners.push((step, location, orientation));
+                orientation = policy.turn(orientation, turns);
+                turns = (turns + 1) % period;
+            } else {
+                location = ahead;
+            }
+            step += 1;
+        };
+
+        let corners = corners
+            .into_iter()
+            .filter(|(at, _, _)| *at >= entered_at)
+            .map(|(_, location, orientation)| (location, orientation))
+            .collect::<Vec<_>>();
+        Some(LoopReport {
+            obstruction,
+            entered_at,
+            cells: step - entered_at - corners.len(),
+            turns: corners.len(),
+            corners,
+        })
+    }
+
+    // the first visits where one more obstruction on the cell traps the guard
+    fn trapping_visits(
+        &self,
+        guard: (Coord, Orientation),
+        policy: &dyn MovementPolicy,
+    ) -> Vec<FirstVisit> {
+        let mut seen = vec![0; self.states(policy)];
+        self.first_visits(guard, policy)
+            .into_iter()
+            .enumerate()
+            .filter(|(i, visit)| {
+                self.loops_from(
+                    (visit.location, visit.orientation, visit.turns),
+                    Some(visit.cell),
+                    policy,
+                    &mut seen,
+                    i + 1,
+                )
+            })
+            .map(|(_, visit)| visit)
+            .collect()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FirstVisit {
+    pub cell: Coord,
+    // where the guard is standing, and facing, just before it steps onto the cell
+    pub location: Coord,
+    pub orientation: Orientation,
+    // how many times it's turned so far, modulo the policy's period
+    pub turns: usize,
+    // counted like `Input::step`, so moving on or turning on the spot are both one step
+    pub step: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoopReport {
+    pub obstruction: Coord,
+    // the first step the guard takes that it'll keep repeating
+    pub entered_at: usize,
+    // how many cells it walks, and how many times it turns, each time round
+    pub cells: usize,
+    pub turns: usize,
+    // where it turns and which way it's facing when it gets there, in the order it reaches them
+    pub corners: Vec<(Coord, Orientation)>,
+}
+
+// every obstruction that traps the guard, in the order the guard would first run into them
+pub fn loop_obstructions(
+    input: &Input,
+    guard: (Coord, Orientation),
+    policy: &dyn MovementPolicy,
+) -> Vec<LoopReport> {
+    let table = JumpTable::new(input);
+    table
+        .trapping_visits(guard, policy)
+        .into_iter()
+        .filter_map(|visit| table.trace_loop(guard, visit.cell, policy))
+        .collect()
+}
+
+impl Input {
+    fn glyph(&self, Coord { x, y }: Coord) -> char {
+        let guard = std::iter::once(&self.guard)
+            .chain(&self.others)
+            .find(|g| g.location == Coord { x, y });
+        if let Some(guard) = guard {
+            match guard.orientation {
+                Orientation::Up => '^',
+                Orientation::Left => '<',
+                Orientation::Right => '>',
+                Orientation::Down => 'v',
+            }
+        } else if self.is_obstruction(Coord { x, y }) {
+            '#'
+        } else {
+            '.'
+        }
+    }
+
+    fn render_with(&self, overlay: impl Fn(Coord) -> Option<char>) -> String {
+        let mut out = String::new();
+        for y in 0..=self.area.size.y {
+            for x in 0..=self.area.size.x {
+                out.push(overlay(Coord { x, y }).unwrap_or_else(|| self.glyph(Coord { x, y })));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    // the guard's usual path as X, with an O on every obstruction that would trap it
+    pub fn render_obstructions(
+        &self,
+        guard: (Coord, Orientation),
+        policy: &dyn MovementPolicy,
+        reports: &[LoopReport],
+    ) -> String {
+        let walked = walk(self, guard, policy).cells;
+        let obstructions = reports
+            .iter()
+            .map(|r| r.obstruction)
+            .collect::<HashSet<_>>();
+        self.render_with(|cell| {
+            if obstructions.contains(&cell) {
+                Some('O')
+            } else if walked.contains(&cell) && cell != guard.0 {
+                Some('X')
+            } else {
+                None
+            }
+        })
+    }
+
+    // one loop drawn the way the puzzle does: | and - along the way and + where it turns or crosses
+    pub fn render_loop(&self, report: &LoopReport) -> String {
+        let mut marks: HashMap<Coord, char> = HashMap::new();
+        let mut mark = |cell: Coord, c: char| {
+            let mark = marks.entry(cell).or_insert(c);
+            if *mark != c {
+                *mark = '+';
+            }
+        };
+        for (i, (from, _)) in report.corners.iter().enumerate() {
+            let (to, _) = report.corners[(i + 1) % report.corners.len()];
+            if from.x == to.x {
+                for y in from.y.min(to.y)..=from.y.max(to.y) {
+                    mark(Coord { x: from.x, y }, '|');
+                }
+            } else {
+                for x in from.x.min(to.x)..=from.x.max(to.x) {
+                    mark(Coord { x, y: from.y }, '-');
+                }
+            }
+        }
+        for (corner, _) in &report.corners {
+            marks.insert(*corner, '+');
+        }
+        marks.insert(report.obstruction, 'O');
+        for (location, _) in self.guards() {
+            marks.remove(&location);
+        }
+        self.render_with(|cell| marks.get(&cell).copied())
+    }
+}
+
+// what a guard does when something's in the way. `turns` counts how many times it's been
+// blocked already, for policies that don't always do the same thing
+pub trait MovementPolicy {
+    fn turn(&self, orientation: Orientation, turns: usize) -> Orientation;
+
+    // how many turns before the policy starts repeating itself, which loop detection needs to
+    // know: two visits to the same cell facing the same way aren't a loop if the next turn differs
+    fn period(&self) -> usize {
+        1
+    }
+}
+
+// a policy that says 0 is taken to mean it never changes
+fn period(policy: &dyn MovementPolicy) -> usize {
+    policy.period().max(1)
+}
+
+// the puzzle's guard
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TurnRight;
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TurnLeft;
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TurnAround;
+
+// right, then left, then right again
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Alternate;
+
+impl MovementPolicy for TurnRight {
+    fn turn(&self, orientation: Orientation, _: usize) -> Orientation {
+        orientation.turn_right()
+    }
+}
+
+impl MovementPolicy for TurnLeft {
+    fn turn(&self, orientation: Orientation, _: usize) -> Orientation {
+        orientation.turn_left()
+    }
+}
+
+impl MovementPolicy for TurnAround {
+    fn turn(&self, orientation: Orientation, _: usize) -> Orientation {
+        orientation.turn_around()
+    }
+}
+
+impl MovementPolicy for Alternate {
+    fn turn(&self, orientation: Orientation, turns: usize) -> Orientation {
+        if turns.is_multiple_of(2) {
+            orientation.turn_right()
+        } else {
+            orientation.turn_left()
+        }
+    }
+
+    fn period(&self) -> usize {
+        2
+    }
+}
+
+// what happens when guards run into each other
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Collisions {
+    // they walk straight through each other, so each guard might as well be on its own
+    #[default]
+    Ignore,
+    // a guard treats the others like obstructions
+    Block,
+    // guards that end up on the same cell stop there for good
+    Halt,
+}
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Outcome {
+    Exited,
+    Looped,
+    Halted,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GuardPath {
+    pub start: (Coord, Orientation),
+    pub cells: HashSet<Coord>,
+    pub outcome: Outcome,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Patrol {
+    // in the same order as `Input::guards`
+    pub guards: Vec<GuardPath>,
+}
+
+impl Patrol {
+    pub fn union(&self) -> HashSet<Coord> {
+        self.guards
+            .iter()
+            .flat_map(|g| g.cells.iter().copied())
+            .collect()
+    }
+
+    pub fn any_looped(&self) -> bool {
+        self.guards.iter().any(|g| g.outcome == Outcome::Looped)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+struct Walker {
+    location: Coord,
+    orientation: Orientation,
+    // turns taken, modulo the policy's period
+    turns: usize,
+    // None while it's still walking
+    outcome: Option<Outcome>,
+}
+
+impl Input {
+    fn ahead(&self, Coord { x, y }: Coord, orientation: Orientation) -> Option<Coord> {
+        let ahead = match orientation {
+            Orientation::Up => Coord {
+                x,
+                y: y.checked_sub(1)?,
+            },
+            Orientation::Left => Coord {
+                x: x.checked_sub(1)?,
+                y,
+            },
+            Orientation::Right => Coord { x: x + 1, y },
+            Orientation::Down => Coord { x, y: y + 1 },
+        };
+        (ahead.x <= self.area.size.x && ahead.y <= self.area.size.y).then_some(ahead)
+    }
+
+    // all the guards a step at a time, in turn, until none of them are still walking or the
+    // whole lot of them are somewhere they've all been together before. only needed when they
+    // get in each other's way - otherwise each one can `walk` on its own
+    fn simulate(
+        &self,
+        guards: &[Guard],
+        policy: &dyn MovementPolicy,
+        collisions: Collisions,
+    ) -> Vec<GuardPath> {
+        let mut walkers = guards
+            .iter()
+            .map(|g| Walker {
+                location: g.location,
+                orientation: g.orientation,
+                turns: 0,
+                outcome: None,
+            })
+            .collect::<Vec<_>>();
+        let mut cells = guards
+            .iter()
+            .map(|g| HashSet::from([g.location]))
+            .collect::<Vec<_>>();
+        let mut seen = HashSet::new();
+
+        while walkers.iter().any(|w| w.outcome.is_none()) {
+            if !seen.insert(walkers.clone()) {
+                for walker in walkers.iter_mut().filter(|w| w.outcome.is_none()) {
+                    walker.outcome = Some(Outcome::Looped);
+                }
+                break;
+            }
+
+            for i in 0..walkers.len() {
+                let walker = walkers[i];
+                if walker.outcome.is_some() {
+                    continue;
+                }
+                let Some(ahead) = self.ahead(walker.location, walker.orientation) else {
+                    walkers[i].outcome = Some(Outcome::Exited);
+                    continue;
+                };
+
+                let blocked = self.is_obstruction(ahead)
+                    || collisions == Collisions::Block
+                        && walkers
+                            .iter()
+                            .any(|w| w.location == ahead && w.outcome != Some(Outcome::Exited));
+                if blocked {
+                    walkers[i].orientation = policy.turn(walker.orientation, walker.turns);
+                    walkers[i].turns = (walker.turns + 1) % period(policy);
+                } else {
+                    walkers[i].location = ahead;
+                    cells[i].insert(ahead);
+                }
+            }
+
+            if collisions == Collisions::Halt {
+                for i in 0..walkers.len() {
+                    let crowded = walkers.iter().enumerate().any(|(j, w)| {
+                        j != i
+                            && w.location == walkers[i].location
+                            && w.outcome != Some(Outcome::Exited)
+                    });
+                    if crowded && walkers[i].outcome.is_none() {
+                        walkers[i].outcome = Some(Outcome::Halted);
+                    }
+                }
+            }
+        }
+
+        walkers
+            .into_iter()
+            .zip(guards)
+            .zip(cells)
+            .map(|((walker, guard), cells)| GuardPath {
+                start: (guard.location, guard.orientation),
+                cells,
+                outcome: walker.outcome.unwrap(),
+            })
+            .collect()
+    }
+
+    // every guard on the map following the same policy
+    pub fn patrol(&self, policy: &dyn MovementPolicy, collisions: Collisions) -> Patrol {
+        let guards = std::iter::once(self.guard)
+            .chain(self.others.iter().copied())
+            .collect::<Vec<_>>();
+        let guards = match collisions {
+            // no need to keep track of every combination of where they all are
+            Collisions::Ignore => guards
+                .iter()
+                .map(|g| walk(self, (g.location, g.orientation), policy))
+                .collect(),
+            _ => self.simulate(&guards, policy, collisions),
+        };
+
+        Patrol { guards }
+    }
+}
+
+pub fn part_1(input: &Input) -> usize {
+    walk(input, input.guard(), &TurnRight).cells.len()
+}
+
+// each candidate is checked from where the guard is just before reaching it, jumping from
+// obstruction to obstruction
+pub fn part_2(input: &Input) -> usize {
+    JumpTable::new(input)
+        .trapping_visits(input.guard(), &TurnRight)
+        .len()
+}
+
+// puts each obstruction in and walks the guard round from the start again
+pub fn part_2_rewalk(input: &Input) -> usize {
+    let mut answer = 0;
+    for cell in walk(input, input.guard(), &TurnRight).cells {
+        if cell == input.guard.location {
+            // can't put a new obstruction where the guard is
+            continue;
+        }
+
+        let mut new_input = input.clone();
+        // try putting an obstruction there
+        new_input
+            .area
+            .obstructions_by_x
+            .entry(cell.x)
+            .or_default()
+            .insert(cell.y);
+        new_input
+            .area
+            .obstructions_by_y
+            .entry(cell.y)
+            .or_default()
+            .insert(cell.x);
+        if walk(&new_input, input.guard(), &TurnRight).outcome == Outcome::Looped {
+            answer += 1;
+        }
+    }
+
+    answer
+}
+
+#[test]
+pub fn test() {
+    let input = r#"....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+"#;
+    let input = parse_input(input);
+    assert_eq!(part_1(&input), 41);
+    assert_eq!(part_2(&input), 6);
+    assert_eq!(part_2_rewalk(&input), 6);
+}
+
+#[test]
+pub fn test_jump_table() {
+    let input = parse_input(
+        "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+",
+    );
+    let table = JumpTable::new(&input);
+    let start = Coord { x: 4, y: 6 };
+    assert_eq!(
+        table.jump(start, Orientation::Up, None),
+        Some(Coord { x: 4, y: 1 })
+    );
+    assert_eq!(
+        table.jump(start, Orientation::Left, None),
+        Some(Coord { x: 2, y: 6 })
+    );
+    assert_eq!(table.jump(start, Orientation::Right, None), None);
+    assert_eq!(table.jump(start, Orientation::Down, None), None);
+    // an extra obstruction past the real one, behind the guard, or in another row changes nothing
+    assert_eq!(
+        table.jump(start, Orientation::Up, Some(Coord { x: 4, y: 0 })),
+        Some(Coord { x: 4, y: 1 })
+    );
+    assert_eq!(
+        table.jump(start, Orientation::Up, Some(Coord { x: 4, y: 8 })),
+        Some(Coord { x: 4, y: 1 })
+    );
+    assert_eq!(
+        table.jump(start, Orientation::Up, Some(Coord { x: 5, y: 3 })),
+        Some(Coord { x: 4, y: 1 })
+    );
+    assert_eq!(
+        table.jump(start, Orientation::Up, Some(Coord { x: 4, y: 3 })),
+        Some(Coord { x: 4, y: 4 })
+    );
+    assert_eq!(
+        table.jump(start, Orientation::Right, Some(Coord { x: 9, y: 6 })),
+        Some(Coord { x: 8, y: 6 })
+    );
+    assert_eq!(
+        table.jump(start, Orientation::Up, Some(Coord { x: 4, y: 5 })),
+        Some(start)
+    );
+
+    assert!(!table.enters_loop(input.guard(), None, &TurnRight));
+    assert!(table.enters_loop(input.guard(), Some(Coord { x: 3, y: 6 }), &TurnRight));
+    let visits = table.first_visits(input.guard(), &TurnRight);
+    assert_eq!(visits.len(), part_1(&input) - 1);
+    assert_eq!(
+        visits[0],
+        FirstVisit {
+            cell: Coord { x: 4, y: 5 },
+            location: start,
+            orientation: Orientation::Up,
+            turns: 0,
+            step: 0
+        }
+    );
+
+    // a guard boxed in on every side turns round and round without moving
+    let input = parse_input(".#.\n#^#\n.#.\n");
+    let table = JumpTable::new(&input);
+    assert!(table.enters_loop(input.guard(), None, &TurnRight));
+    assert!(table.first_visits(input.guard(), &TurnRight).is_empty());
+    assert_eq!(part_2(&input), 0);
+
+    // against re-walking from the start, on maps where the guard crosses its own path
+    for (map, loops) in [
+        (
+            "..#....\n.#..#..\n#....#.\n...#.#.\n##^#...\n.#.....\n...#...\n",
+            7,
+        ),
+        (
+            "...###.\n.##.#.#\n.......\n.......\n.#...^.\n..#..#.\n...#.#.\n",
+            5,
+        ),
+        (
+            "..#....\n#.#...#\n#...#..\n.......\n.#....v\n#.....#\n...#...\n",
+            4,
+        ),
+        (
+            "#.....#\n..#..#.\n.#...<#\n.......\n...#..#\n....##.\n#...#.#\n",
+            5,
+        ),
+    ] {
+        let input = parse_input(map);
+        assert_eq!(part_2(&input), loops, "{}", map);
+        assert_eq!(part_2_rewalk(&input), loops, "{}", map);
+    }
+}
+
+#[test]
+pub fn test_loop_obstructions() {
+    let input = parse_input(
+        "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+",
+    );
+    let reports = loop_obstructions(&input, input.guard(), &TurnRight);
+    assert_eq!(reports.len(), part_2(&input));
+    assert_eq!(
+        reports
+            .iter()
+            .map(|r| r.obstruction.into())
+            .collect::<Vec<(usize, usize)>>(),
+        vec![(3, 6), (6, 7), (3, 8), (1, 8), (7, 7), (7, 9)]
+    );
+
+    // the puzzle's first example: the guard only runs into it coming back along its starting row,
+    // but it's been walking the loop from the start
+    let report = &reports[0];
+    assert_eq!(report.entered_at, 0);
+    assert_eq!((report.cells, report.turns), (18, 4));
+    assert_eq!(
+        report.corners,
+        vec![
+            (Coord { x: 4, y: 1 }, Orientation::Up),
+            (Coord { x: 8, y: 1 }, Orientation::Right),
+            (Coord { x: 8, y: 6 }, Orientation::Down),
+            (Coord { x: 4, y: 6 }, Orientation::Left),
+        ]
+    );
+    assert_eq!(
+        input.render_loop(report),
+        "....#.....
+....+---+#
+....|...|.
+..#.|...|.
+....|..#|.
+....|...|.
+.#.O^---+.
+........#.
+#.........
+......#...
+"
+    );
+    assert_eq!(
+        input.render_obstructions(input.guard(), &TurnRight, &reports),
+        "....#.....
+....XXXXX#
+....X...X.
+..#.X...X.
+..XXXXX#X.
+..X.X.X.X.
+.#XO^XXXX.
+.XXXXXOO#.
+#OXOXXXX..
+......#O..
+"
+    );
+
+    // the guard's already standing there
+    assert!(
+        JumpTable::new(&input)
+            .trace_loop(input.guard(), input.guard().0, &TurnRight)
+            .is_none()
+    );
+
+    // every report really is a loop, found the slow way
+    for report in &reports {
+        let mut blocked = input.clone();
+        blocked
+            .area
+            .obstructions_by_x
+            .entry(report.obstruction.x)
+            .or_default()
+            .insert(report.obstruction.y);
+        blocked
+            .area
+            .obstructions_by_y
+            .entry(report.obstruction.y)
+            .or_default()
+            .insert(report.obstruction.x);
+        let mut states = HashMap::new();
+        let mut step = 0;
+        let first = loop {
+            if let Some(first) = states.insert(blocked.guard(), step) {
+                break first;
+            }
+            blocked = blocked.step().expect("the guard should be trapped");
+            step += 1;
+        };
+        assert_eq!(first, report.entered_at);
+        assert_eq!(step - first, report.cells + report.turns);
+    }
+}
+
+#[test]
+pub fn test_patrol() {
+    let example = parse_input(
+        "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+",
+    );
+    let patrol = example.patrol(&TurnRight, Collisions::Ignore);
+    assert_eq!(patrol.guards.len(), 1);
+    assert_eq!(patrol.guards[0].cells.len(), part_1(&example));
+    assert_eq!(patrol.guards[0].outcome, Outcome::Exited);
+    assert!(!patrol.any_looped());
+
+    let left = example.patrol(&TurnLeft, Collisions::Ignore);
+    assert_eq!(left.guards[0].outcome, Outcome::Exited);
+    assert_eq!(left.guards[0].cells.len(), 10);
+    // straight up to the obstruction, then back down and off the bottom
+    let around = example.patrol(&TurnAround, Collisions::Ignore);
+    assert_eq!(around.guards[0].cells.len(), 9);
+
+    // right then left zigzags it up and off the top
+    let input = parse_input(
+        "#....
+....#
+...#.
+.....
+^....
+",
+    );
+    assert_eq!(
+        input.patrol(&Alternate, Collisions::Ignore).guards[0].outcome,
+        Outcome::Exited
+    );
+    // turning right every time, the guard gets stuck between the same four obstructions
+    let boxed = parse_input(
+        ".#...
+....#
+.^...
+#....
+...#.
+",
+    );
+    assert!(boxed.patrol(&TurnRight, Collisions::Ignore).any_looped());
+    // whereas always turning left, it can get out
+    assert!(!boxed.patrol(&TurnLeft, Collisions::Ignore).any_looped());
+
+    // two guards walking towards each other along a corridor
+    let input = parse_input(
+        "#####
+>...<
+#####
+",
+    );
+    assert_eq!(
+        input.guards(),
+        vec![
+            (Coord { x: 0, y: 1 }, Orientation::Right),
+            (Coord { x: 4, y: 1 }, Orientation::Left),
+        ]
+    );
+    assert_eq!(input.to_string(), "#####\n>...<\n#####\n");
+
+    let ignore = input.patrol(&TurnAround, Collisions::Ignore);
+    assert!(
+        ignore
+            .guards
+            .iter()
+            .all(|g| g.outcome == Outcome::Exited && g.cells.len() == 5)
+    );
+    assert_eq!(ignore.union().len(), 5);
+
+    // they meet in the middle and stop there
+    let halt = input.patrol(&TurnAround, Collisions::Halt);
+    assert_eq!(halt.guards[0].outcome, Outcome::Halted);
+    assert_eq!(halt.guards[1].outcome, Outcome::Halted);
+    assert_eq!(halt.guards[0].cells.len(), 3);
+
+    // they bounce off each other and walk back out the way they came
+    let block = input.patrol(&TurnAround, Collisions::Block);
+    assert!(block.guards.iter().all(|g| g.outcome == Outcome::Exited));
+    // the first guard goes first, so it gets to the middle
+    assert_eq!(block.guards[0].cells.len(), 3);
+    assert_eq!(block.guards[1].cells.len(), 2);
+    assert_eq!(block.union().len(), 5);
+
+    // turning right instead, they block each other's way out forever
+    let input = parse_input(
+        "#####
+#>.<#
+#####
+",
+    );
+    assert!(
+        input
+            .patrol(&TurnRight, Collisions::Block)
+            .guards
+            .iter()
+            .all(|g| g.outcome == Outcome::Looped)
+    );
+}
+
+#[test]
+pub fn test_step() {
+    let input = parse_input(
+        ".#.
+...
+.^.
+",
+    );
+    let input = input.step().unwrap();
+    assert_eq!(input.to_string(), ".#.\n.^.\n...\n");
+    // blocked, so it turns on the spot
+    let input = input.step().unwrap();
+    assert_eq!(input.to_string(), ".#.\n.>.\n...\n");
+    let input = input.step().unwrap();
+    assert_eq!(input.guard(), (Coord { x: 2, y: 1 }, Orientation::Right));
+    assert!(input.step().is_none());
+
+    // walking it one step at a time covers the same ground as part 1
+    let mut input = parse_input(
+        "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+",
+    );
+    let mut visited = HashSet::from([input.guard().0]);
+    while let Some(next) = input.step() {
+        visited.insert(next.guard().0);
+        input = next;
+    }
+    assert_eq!(visited.len(), 41);
+}
+
+#[test]
+pub fn test_policies() {
+    // a policy claiming a period of 0 mustn't break the loop detection
+    struct Never;
+    impl MovementPolicy for Never {
+        fn turn(&self, orientation: Orientation, _: usize) -> Orientation {
+            orientation.turn_right()
+        }
+
+        fn period(&self) -> usize {
+            0
+        }
+    }
+
+    let maps = [
+        "....#.....\n.........#\n..........\n..#.......\n.......#..\n..........\n.#..^.....\n........#.\n#.........\n......#...\n",
+        "..#....\n.#..#..\n#....#.\n...#.#.\n##^#...\n.#.....\n...#...\n",
+        "#.....#\n..#..#.\n.#...<#\n.......\n...#..#\n....##.\n#...#.#\n",
+        "#....\n....#\n...#.\n.....\n^....\n",
+    ];
+    let policies: [&dyn MovementPolicy; 5] =
+        [&TurnRight, &TurnLeft, &TurnAround, &Alternate, &Never];
+    for map in maps {
+        let input = parse_input(map);
+        for policy in policies {
+            let path = walk(&input, input.guard(), policy);
+            let table = JumpTable::new(&input);
+            assert_eq!(
+                table.enters_loop(input.guard(), None, policy),
+                path.outcome == Outcome::Looped,
+                "{}",
+                map
+            );
+
+            // every obstruction that traps the guard, found by walking it round with each one in turn
+            let mut expected = path
+                .cells
+                .iter()
+                .filter(|cell| **cell != input.guard().0)
+                .filter(|cell| {
+                    let mut blocked = input.clone();
+                    blocked
+                        .area
+                        .obstructions_by_x
+                        .entry(cell.x)
+                        .or_default()
+                        .insert(cell.y);
+                    blocked
+                        .area
+                        .obstructions_by_y
+                        .entry(cell.y)
+                        .or_default()
+                        .insert(cell.x);
+                    walk(&blocked, input.guard(), policy).outcome == Outcome::Looped
+                })
+                .copied()
+                .collect::<Vec<_>>();
+            let mut found = loop_obstructions(&input, input.guard(), policy)
+                .into_iter()
+                .map(|r| r.obstruction)
+                .collect::<Vec<_>>();
+            let order = |c: &Coord| (c.y, c.x);
+            expected.sort_by_key(order);
+            found.sort_by_key(order);
+            assert_eq!(found, expected, "{}", map);
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+pub fn test_json() {
+    let input = parse_input(".#.\n...\n.^.\n");
+    let json = serde_json::to_string(&input).unwrap();
+    let reloaded: Input = serde_json::from_str(&json).unwrap();
+    assert_eq!(part_1(&reloaded), part_1(&input));
+
+    let area = |by_x: &str, by_y: &str| {
+        format!(
+            r#""area":{{"obstructions_by_x":{},"obstructions_by_y":{},"size":{{"x":2,"y":2}}}}"#,
+            by_x, by_y
+        )
+    };
+    let guard = |x: usize, y: usize| {
+        format!(
+            r#""guard":{{"location":{{"x":{},"y":{}}},"orientation":"Up"}}"#,
+            x, y
+        )
+    };
+    let load = |area: String, guard: String| {
+        serde_json::from_str::<Input>(&format!("{{{},{}}}", area, guard))
+    };
+    assert!(load(area(r#"{"1":[0]}"#, r#"{"0":[1]}"#), guard(1, 2)).is_ok());
+    // the lists disagree, an obstruction or the guard is off the map, or the guard's on an obstruction
+    assert!(load(area(r#"{"1":[0]}"#, r#"{"0":[2]}"#), guard(1, 2)).is_err());
+    assert!(load(area(r#"{"1":[0]}"#, r#"{}"#), guard(1, 2)).is_err());
+    assert!(load(area(r#"{"5":[0]}"#, r#"{"0":[5]}"#), guard(1, 2)).is_err());
+    assert!(load(area(r#"{"1":[0]}"#, r#"{"0":[1]}"#), guard(1, 3)).is_err());
+    assert!(load(area(r#"{"1":[0]}"#, r#"{"0":[1]}"#), guard(1, 0)).is_err());
+}
@@ -0,0 +1,457 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ops::ControlFlow,
+};
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Calibration {
+    target: u128,
+    values: Vec<u128>,
+}
+
+impl Calibration {
+    pub fn is_valid(&self) -> bool {
+        self.solve(Operator::BASIC).is_some()
+    }
+
+    pub fn is_valid_with_concat(&self) -> bool {
+        self.solve(Operator::ALL).is_some()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Operator {
+    Add,
+    Multiply,
+    Concat,
+}
+
+impl Operator {
+    // the operators for each part
+    pub const BASIC: &[Operator] = &[Operator::Add, Operator::Multiply];
+    pub const ALL: &[Operator] = &[Operator::Add, Operator::Multiply, Operator::Concat];
+
+    // None if the result doesn't fit
+    pub fn apply(self, a: u128, b: u128) -> Option<u128> {
+        match self {
+            Operator::Add => a.checked_add(b),
+            Operator::Multiply => a.checked_mul(b),
+            Operator::Concat => match 10u128.checked_pow(digits(b)) {
+                Some(shift) => a.checked_mul(shift)?.checked_add(b),
+                // b's already as long as a u128 gets, so there's only room if a is 0
+                None => (a == 0).then_some(b),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operator::Add => write!(f, "+"),
+            Operator::Multiply => write!(f, "*"),
+            Operator::Concat => write!(f, "||"),
+        }
+    }
+}
+
+fn digits(n: u128) -> u32 {
+    n.checked_ilog10().unwrap_or(0) + 1
+}
+
+// which operator goes between each pair of values to hit the target, evaluated left to right
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Witness {
+    pub values: Vec<u128>,
+    pub operators: Vec<Operator>,
+}
+
+impl std::fmt::Display for Witness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.values[0])?;
+        for (operator, value) in self.operators.iter().zip(&self.values[1..]) {
+            write!(f, " {} {}", operator, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+// the assignment with the smallest result, if there's one that doesn't overflow. a bigger
+// running total never gives a smaller result with any of the operators, so taking the smallest
+// at each step is as small as it gets
+fn smallest(values: &[u128], operators: &[Operator]) -> Option<(u128, Vec<Operator>)> {
+    let (first, rest) = values.split_first()?;
+    let mut total = *first;
+    let mut chosen = Vec::new();
+    for value in rest {
+        let (next, operator) = operators
+            .iter()
+            .filter_map(|o| Some((o.apply(total, *value)?, *o)))
+            .min_by_key(|(next, _)| *next)?;
+        total = next;
+        chosen.push(operator);
+    }
+
+    Some((total, chosen))
+}
+
+impl Calibration {
+    pub fn target(&self) -> u128 {
+        self.target
+    }
+
+    pub fn values(&self) -> &[u128] {
+        &self.values
+    }
+
+    // nothing makes a running total smaller except multiplying by 0, so until the last 0
+    // has gone by there's no giving up on a total that's overshot
+    fn last_zero(&self) -> Option<usize> {
+        self.values.iter().rposition(|v| *v == 0)
+    }
+
+    // depth first through every assignment that can still work, handing each one that
+    // hits the target to `visit`, which says whether to keep going
+    fn each_solution(
+        &self,
+        operators: &[Operator],
+        mut visit: impl FnMut(&[Operator]) -> ControlFlow<()>,
+    ) {
+        fn search(
+            calibration: &Calibration,
+            operators: &[Operator],
+            last_zero: Option<usize>,
+            total: u128,
+            chosen: &mut Vec<Operator>,
+            visit: &mut impl FnMut(&[Operator]) -> ControlFlow<()>,
+        ) -> ControlFlow<()> {
+            let index = chosen.len() + 1;
+            if index == calibration.values.len() {
+                if total == calibration.target {
+                    return visit(chosen);
+                }
+                return ControlFlow::Continue(());
+            }
+            if total > calibration.target && last_zero.is_none_or(|z| z < index) {
+                return ControlFlow::Continue(());
+            }
+
+            for operator in operators {
+                if let Some(total) = operator.apply(total, calibration.values[index]) {
+                    chosen.push(*operator);
+                    let flow = search(calibration, operators, last_zero, total, chosen, visit);
+                    chosen.pop();
+                    flow?;
+                }
+            }
+
+            ControlFlow::Continue(())
+        }
+
+        if let Some(first) = self.values.first() {
+            let _ = search(self, operators, self.last_zero(), *first, &mut Vec::new(), &mut visit);
+        }
+    }
+
+    fn witness_for(&self, operators: &[Operator]) -> Witness {
+        Witness {
+            values: self.values.clone(),
+            operators: operators.to_vec(),
+        }
+    }
+
+    pub fn witness(&self, operators: &[Operator]) -> Option<Witness> {
+        self.solve(operators).map(|chosen| self.witness_for(&chosen))
+    }
+
+    // the first assignment trying the values left to right comes across - not always the
+    // same one `witness` finds working back from the target
+    pub fn witness_forwards(&self, operators: &[Operator]) -> Option<Witness> {
+        let mut found = None;
+        self.each_solution(operators, |chosen| {
+            found = Some(self.witness_for(chosen));
+            ControlFlow::Break(())
+        });
+        found
+    }
+
+    // works back from the target, peeling values off the end: the last operator can only have
+    // been + if the value fits under the target, * if it divides it, and || if the target ends
+    // in its digits. that rules out nearly everything straight away, and remembering which
+    // (values left, target) pairs have already failed stops long lines of 1s and 0s from blowing up
+    pub fn solve(&self, operators: &[Operator]) -> Option<Vec<Operator>> {
+        fn search(
+            values: &[u128],
+            target: u128,
+            operators: &[Operator],
+            failed: &mut HashSet<(usize, u128)>,
+            chosen: &mut Vec<Operator>,
+        ) -> bool {
+            let Some((last, rest)) = values.split_last() else {
+                return false;
+            };
+            if rest.is_empty() {
+                return target == *last;
+            }
+            if failed.contains(&(values.len(), target)) {
+                return false;
+            }
+
+            for operator in operators {
+                let found = match operator {
+                    Operator::Add => target
+                        .checked_sub(*last)
+                        .is_some_and(|target| search(rest, target, operators, failed, chosen)),
+                    // anything times 0 is 0, so whatever comes before is fine as long as it fits
+                    Operator::Multiply if *last == 0 => {
+                        target == 0
+                            && smallest(rest, operators).is_some_and(|(_, ops)| {
+                                chosen.extend(ops);
+                                true
+                            })
+                    }
+                    Operator::Multiply => {
+                        target.is_multiple_of(*last)
+                            && search(rest, target / last, operators, failed, chosen)
+                    }
+                    Operator::Concat => match 10u128.checked_pow(digits(*last)) {
+                        Some(shift) => {
+                            target % shift == *last
+                                && search(rest, target / shift, operators, failed, chosen)
+                        }
+                        None => target == *last && search(rest, 0, operators, failed, chosen),
+                    },
+                };
+                // the innermost call finishes first, so this builds up from the left
+                if found {
+                    chosen.push(*operator);
+                    return true;
+                }
+            }
+
+            failed.insert((values.len(), target));
+            false
+        }
+
+        let mut chosen = Vec::new();
+        let mut failed = HashSet::new();
+        search(&self.values, self.target, operators, &mut failed, &mut chosen).then_some(chosen)
+    }
+
+    pub fn witnesses(&self, operators: &[Operator]) -> Vec<Witness> {
+        let mut found = Vec::new();
+        self.each_solution(operators, |chosen| {
+            found.push(self.witness_for(chosen));
+            ControlFlow::Continue(())
+        });
+        found
+    }
+
+    // how many assignments hit the target, without listing them: it's enough to know how
+    // many ways there are to reach each running total (saturating, in case of lots of zeros)
+    pub fn count_solutions(&self, operators: &[Operator]) -> u64 {
+        let Some(first) = self.values.first() else {
+            return 0;
+        };
+        let last_zero = self.last_zero();
+        let mut totals = HashMap::from([(*first, 1u64)]);
+        for (index, arg) in self.values.iter().enumerate().skip(1) {
+            let mut next: HashMap<u128, u64> = HashMap::new();
+            for (total, ways) in totals {
+                if total > self.target && last_zero.is_none_or(|z| z < index) {
+                    continue;
+                }
+                for operator in operators {
+                    if let Some(total) = operator.apply(total, *arg) {
+                        let count = next.entry(total).or_default();
+                        *count = count.saturating_add(ways);
+                    }
+                }
+            }
+            totals = next;
+        }
+
+        totals.get(&self.target).copied().unwrap_or(0)
+    }
+}
+
+pub use common::ParseError;
+
+pub fn try_parse_input(input: &str) -> Result<Vec<Calibration>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(number, line)| {
+            let error = || ParseError(format!("line {}: calibrations look like 190: 10 19", number + 1));
+            let (target, values) = line.split_once(": ").ok_or_else(error)?;
+            Ok(Calibration {
+                target: target.parse().map_err(|_| error())?,
+                values: values
+                    .split_ascii_whitespace()
+                    .map(|v| v.parse().map_err(|_| error()))
+                    .collect::<Result<_, _>>()?,
+            })
+        })
+        .collect()
+}
+
+pub fn parse_input(input: &str) -> Vec<Calibration> {
+    try_parse_input(input).unwrap()
+}
+
+pub fn part_1(input: &[Calibration]) -> u128 {
+    input
+        .iter()
+        .filter(|c| c.is_valid())
+        .map(|c| c.target)
+        .sum()
+}
+
+pub fn part_2(input: &[Calibration]) -> u128 {
+    input
+        .iter()
+        .filter(|c| c.is_valid_with_concat())
+        .map(|c| c.target)
+        .sum()
+}
+
+// the same, searching forwards from the first value instead
+pub fn part_2_forward(input: &[Calibration]) -> u128 {
+    input
+        .iter()
+        .filter(|c| c.witness_forwards(Operator::ALL).is_some())
+        .map(|c| c.target)
+        .sum()
+}
+
+#[test]
+pub fn test() {
+    let input = r#"190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20
+"#;
+    let input = parse_input(input);
+    assert_eq!(part_1(&input), 3749);
+    assert_eq!(part_2(&input), 11387);
+    assert_eq!(part_2_forward(&input), 11387);
+}
+
+#[test]
+pub fn test_witnesses() {
+    let input = parse_input(
+        "190: 10 19
+3267: 81 40 27
+83: 17 5
+7290: 6 8 6 15
+292: 11 6 16 20
+3: 7 0 3
+",
+    );
+
+    assert_eq!(input[0].witness(Operator::BASIC).unwrap().to_string(), "10 * 19");
+    let both = input[1].witnesses(Operator::BASIC);
+    assert_eq!(
+        both.iter().map(|w| w.to_string()).collect::<Vec<_>>(),
+        vec!["81 + 40 * 27", "81 * 40 + 27"]
+    );
+    assert_eq!(input[1].count_solutions(Operator::BASIC), 2);
+    assert_eq!(input[2].witness(Operator::ALL), None);
+    assert_eq!(input[2].count_solutions(Operator::ALL), 0);
+    assert_eq!(input[3].witness(Operator::BASIC), None);
+    assert_eq!(input[3].witness(Operator::ALL).unwrap().to_string(), "6 * 8 || 6 * 15");
+    assert_eq!(input[4].witness(Operator::BASIC).unwrap().to_string(), "11 + 6 * 16 + 20");
+
+    // the 7 overshoots, but multiplying by 0 brings it back down
+    assert_eq!(input[5].count_solutions(Operator::BASIC), 1);
+    assert_eq!(
+        input[5]
+            .witnesses(Operator::ALL)
+            .iter()
+            .map(|w| w.to_string())
+            .collect::<Vec<_>>(),
+        vec!["7 * 0 + 3", "7 * 0 || 3"]
+    );
+    assert_eq!(input[5].count_solutions(Operator::ALL), 2);
+
+    // counting and listing always agree
+    for calibration in &input {
+        for operators in [Operator::BASIC, Operator::ALL] {
+            assert_eq!(
+                calibration.count_solutions(operators),
+                calibration.witnesses(operators).len() as u64
+            );
+        }
+    }
+
+    assert_eq!(Operator::Concat.apply(12, 345), Some(12345));
+    assert_eq!(Operator::Concat.apply(12, 0), Some(120));
+    assert_eq!(Operator::Concat.apply(u128::MAX / 10, 99), None);
+    assert_eq!(Operator::Concat.apply(0, u128::MAX), Some(u128::MAX));
+}
+
+#[test]
+pub fn test_solve() {
+    // concatenating past u64 used to panic
+    let input = parse_input(
+        "18446744073709551616: 1844674407370955161 6
+340282366920938463463374607431768211455: 34028236692093846346 3374607431768211455
+",
+    );
+    assert_eq!(input[0].solve(Operator::ALL), Some(vec![Operator::Concat]));
+    assert!(!input[0].is_valid());
+    assert!(input[1].is_valid_with_concat());
+    assert!(try_parse_input(&format!("{}0: 1 2", u128::MAX)).is_err());
+
+    // hundreds of 1s: + and * both work almost everywhere, which is hopeless without remembering
+    let ones = format!("250: {}", vec!["1"; 300].join(" "));
+    let calibration = &parse_input(&ones)[0];
+    let witness = calibration.witness(Operator::ALL).unwrap();
+    let total = witness
+        .operators
+        .iter()
+        .zip(&witness.values[1..])
+        .fold(witness.values[0], |total, (o, v)| o.apply(total, *v).unwrap());
+    assert_eq!(total, 250);
+    // adding them all up is as big as it gets without ||
+    assert!(!parse_input(&format!("301: {}", vec!["1"; 300].join(" ")))[0].is_valid());
+
+    // multiplying by 0 at the end works whatever comes before, as long as that fits
+    let zero = parse_input(&format!("0: {} {} 0", u128::MAX, u128::MAX))[0].clone();
+    assert!(!zero.is_valid());
+    let zero = parse_input(&format!("0: {} 1 0", u128::MAX))[0].clone();
+    assert_eq!(
+        zero.witness(Operator::BASIC).unwrap().to_string(),
+        format!("{} * 1 * 0", u128::MAX)
+    );
+
+    // against the forward search, on every line of small values
+    for target in 0..=40 {
+        for a in 0..4 {
+            for b in 0..4 {
+                for c in 0..4 {
+                    let calibration = Calibration {
+                        target,
+                        values: vec![a, b, c, 2],
+                    };
+                    for operators in [Operator::BASIC, Operator::ALL] {
+                        let forwards = calibration.count_solutions(operators) > 0;
+                        let backwards = calibration.solve(operators).is_some();
+                        assert_eq!(backwards, forwards, "{:?}", calibration);
+                        if let Some(witness) = calibration.witness(operators) {
+                            assert!(calibration.witnesses(operators).contains(&witness));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::fmt::Formatter;
+use winnow::ascii::dec_int;
+use winnow::combinator::{preceded, separated, separated_pair};
+use winnow::{ModalResult, Parser};
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Robot {
+    position: (i64, i64),
+    velocity: (i64, i64),
+}
+
+impl Robot {
+    pub fn step(self, steps: i64, room_dimensions: (i64, i64)) -> Self {
+        let x_steps = steps % room_dimensions.0;
+        let y_steps = steps % room_dimensions.1;
+        let new_position = (
+            (self.position.0 + self.velocity.0 * x_steps) % room_dimensions.0,
+            (self.position.1 + self.velocity.1 * y_steps) % room_dimensions.1,
+        );
+
+        Self {
+            // readjust so always positive
+            position: (
+                (new_position.0 + room_dimensions.0) % room_dimensions.0,
+                (new_position.1 + room_dimensions.1) % room_dimensions.1,
+            ),
+            velocity: self.velocity,
+        }
+    }
+}
+
+pub fn parse_robot(input: &mut &str) -> ModalResult<Robot> {
+    let ((px, py), (vx, vy)) = separated_pair(
+        preceded("p=", separated_pair(dec_int, ",", dec_int)),
+        " ",
+        preceded("v=", separated_pair(dec_int, ",", dec_int)),
+    )
+    .parse_next(input)?;
+    Ok(Robot {
+        position: (px, py),
+        velocity: (vx, vy),
+    })
+}
+
+pub fn parse_items(input: &mut &str) -> ModalResult<Vec<Robot>> {
+    separated(1.., parse_robot, "\n").parse_next(input)
+}
+
+pub use common::ParseError;
+
+pub fn try_parse_input(input: &str) -> Result<Vec<Robot>, ParseError> {
+    parse_items
+        .parse(input.trim())
+        .map_err(|e| ParseError(e.to_string()))
+}
+
+pub fn parse_input(input: &str) -> Vec<Robot> {
+    try_parse_input(input).unwrap()
+}
+
+pub fn part_1(input: &[Robot], room_dimensions: (i64, i64)) -> i64 {
+    room_after(input, 100, room_dimensions)
+        .quadrant_counts()
+        .iter()
+        .product()
+}
+
+// the robots repeat after width * height steps, so if there's no picture by then there never will be
+pub fn part_2(input: &[Robot], room_dimensions: (i64, i64)) -> Option<i64> {
+    let mut old_locations = input.to_vec();
+
+    for i in 1..=(room_dimensions.0 * room_dimensions.1) {
+        let new_locations = old_locations
+            .iter()
+            .map(|r| r.step(1, room_dimensions))
+            .collect::<Vec<_>>();
+
+        let mut robots_by_location = HashMap::new();
+        for robot in &new_locations {
+            robots_by_location.insert(robot.position, 1);
+        }
+
+        let image = format!("{}", Room {
+            robots: robots_by_location,
+            room_dimensions
+        });
+
+        if image.contains("##########") {
+            return Some(i);
+        }
+
+        old_locations = new_locations;
+    }
+
+    None
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Room {
+    #[cfg_attr(feature = "serde", serde(with = "common::map_as_pairs"))]
+    robots: HashMap<(i64, i64), i64>,
+    room_dimensions: (i64, i64),
+}
+
+impl Room {
+    pub fn robots(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.robots.keys().copied()
+    }
+
+    pub fn room_dimensions(&self) -> (i64, i64) {
+        self.room_dimensions
+    }
+
+    // upper left, upper right, lower left, lower right - anyone on the middle lines doesn't count
+    pub fn quadrant_counts(&self) -> [i64; 4] {
+        let middle = (self.room_dimensions.0 / 2, self.room_dimensions.1 / 2);
+        let mut counts = [0; 4];
+        for (&(x, y), &robots) in &self.robots {
+            if x == middle.0 || y == middle.1 {
+                continue;
+            }
+            counts[(x > middle.0) as usize + 2 * (y > middle.1) as usize] += robots;
+        }
+
+        counts
+    }
+}
+
+// where everyone is after the given number of steps
+pub fn room_after(input: &[Robot], steps: i64, room_dimensions: (i64, i64)) -> Room {
+    let mut robots = HashMap::new();
+    for robot in input {
+        *robots.entry(robot.step(steps, room_dimensions).position).or_insert(0) += 1;
+    }
+
+    Room {
+        robots,
+        room_dimensions,
+    }
+}
+
+impl std::fmt::Display for Room {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        for y in 0..self.room_dimensions.1 {
+            for x in 0..self.room_dimensions.0 {
+                if self.robots.contains_key(&(x, y)) {
+                    write!(f, "#")?;
+                } else {
+                    write!(f, ".")?;
+                }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+pub fn test_1() {
+    let input = "p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3
+";
+
+    let input = parse_input(input);
+
+    assert_eq!(part_1(&input, (11, 7)), 12);
+    assert_eq!(part_2(&input, (11, 7)), None);
+
+    let room = room_after(&input, 100, (11, 7));
+    assert_eq!(room.robots().count(), 10);
+    assert_eq!(room.quadrant_counts(), [1, 3, 4, 1]);
+    let image = room.to_string();
+    assert_eq!(image.lines().count(), 7);
+    assert_eq!(image.lines().next(), Some("......#..#."));
+}
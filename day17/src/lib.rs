@@ -0,0 +1,464 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Program {
+    numbers: Vec<u8>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Registers {
+    a: u64,
+    b: u64,
+    c: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedComputer"))]
+pub struct Computer {
+    program: Program,
+    instruction_pointer: usize,
+    registers: Registers,
+    output: Vec<u64>,
+}
+
+// a computer loaded from json, which has to be checked the way `Computer::new` checks a parsed one
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedComputer {
+    program: Program,
+    instruction_pointer: usize,
+    registers: Registers,
+    output: Vec<u64>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedComputer> for Computer {
+    type Error = ParseError;
+
+    fn try_from(unchecked: UncheckedComputer) -> Result<Self, ParseError> {
+        let registers = unchecked.registers;
+        let computer = Computer::new(
+            unchecked.program.numbers,
+            (registers.a, registers.b, registers.c),
+        )?;
+        // a jump can leave the pointer anywhere up to 7, which just means the program has halted
+        if unchecked.instruction_pointer > computer.program.numbers.len().max(7) {
+            return Err(ParseError(format!(
+                "the instruction pointer {} is past the end of the program",
+                unchecked.instruction_pointer
+            )));
+        }
+
+        Ok(Computer {
+            instruction_pointer: unchecked.instruction_pointer,
+            output: unchecked.output,
+            ..computer
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VmError {
+    // combo operand 7 is reserved and "will not appear in valid programs"
+    ReservedOperand { instruction_pointer: usize },
+    StepLimit(usize),
+}
+
+impl std::fmt::Display for VmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VmError::ReservedOperand { instruction_pointer } => {
+                write!(f, "reserved combo operand 7 at {}", instruction_pointer)
+            }
+            VmError::StepLimit(steps) => write!(f, "still running after {} steps", steps),
+        }
+    }
+}
+
+impl std::error::Error for VmError {}
+
+// shifting by 64 or more would overflow, but dividing by 2^64 or more is just 0
+fn shift(value: u64, by: u64) -> u64 {
+    u32::try_from(by)
+        .ok()
+        .and_then(|by| value.checked_shr(by))
+        .unwrap_or(0)
+}
+
+impl Computer {
+    pub fn new(program: Vec<u8>, (a, b, c): (u64, u64, u64)) -> Result<Self, ParseError> {
+        if let Some(n) = program.iter().find(|n| **n > 7) {
+            return Err(ParseError(format!("{} isn't a three-bit number", n)));
+        }
+
+        Ok(Self {
+            program: Program { numbers: program },
+            instruction_pointer: 0,
+            registers: Registers { a, b, c },
+            output: Vec::new(),
+        })
+    }
+
+    pub fn step(&self) -> Option<Computer> {
+        self.try_step().unwrap()
+    }
+
+    pub fn try_step(&self) -> Result<Option<Computer>, VmError> {
+        if self.instruction_pointer + 1 >= self.program.numbers.len() {
+            return Ok(None);
+        }
+
+        let mut new_state = self.clone();
+
+        let opcode = self.program.numbers[self.instruction_pointer];
+        let literal_operand = self.program.numbers[self.instruction_pointer + 1] as u64;
+
+        // only looked at by the instructions that take a combo operand, which is
+        // what makes 7 a perfectly good literal operand
+        let combo_operand = || match literal_operand {
+            0..=3 => Ok(literal_operand),
+            4 => Ok(self.registers.a),
+            5 => Ok(self.registers.b),
+            6 => Ok(self.registers.c),
+            _ => Err(VmError::ReservedOperand {
+                instruction_pointer: self.instruction_pointer,
+            }),
+        };
+
+        let mut jumped = false;
+        match opcode {
+            0 => {
+                new_state.registers.a = shift(self.registers.a, combo_operand()?);
+            }
+            1 => {
+                new_state.registers.b = self.registers.b ^ literal_operand;
+            }
+            2 => {
+                new_state.registers.b = combo_operand()? & 0b0111;
+            }
+            3 => {
+                if self.registers.a != 0 {
+                    new_state.instruction_pointer = literal_operand as usize;
+                    jumped = true;
+                }
+            }
+            4 => {
+                new_state.registers.b = self.registers.b ^ self.registers.c;
+            }
+            5 => {
+                new_state.output.push(combo_operand()? % 8);
+            }
+            6 => {
+                new_state.registers.b = shift(self.registers.a, combo_operand()?);
+            }
+            7 => {
+                new_state.registers.c = shift(self.registers.a, combo_operand()?);
+            }
+            _ => unreachable!("programs only hold three-bit numbers"),
+        }
+
+        if !jumped {
+            new_state.instruction_pointer += 2;
+        }
+
+        Ok(Some(new_state))
+    }
+
+    // like run, but gives up on programs that don't halt within the given number of steps
+    pub fn run_for(&self, max_steps: usize) -> Result<Vec<u64>, VmError> {
+        let mut state = self.clone();
+        for _ in 0..max_steps {
+            match state.try_step()? {
+                Some(new_state) => state = new_state,
+                None => return Ok(state.output),
+            }
+        }
+
+        Err(VmError::StepLimit(max_steps))
+    }
+
+    pub fn run(&self) -> Vec<u64> {
+        let mut state = self.clone();
+        while let Some(new_state) = state.step() {
+            state = new_state;
+        }
+        state.output.clone()
+    }
+
+    pub fn registers(&self) -> (u64, u64, u64) {
+        (self.registers.a, self.registers.b, self.registers.c)
+    }
+
+    pub fn instruction_pointer(&self) -> usize {
+        self.instruction_pointer
+    }
+
+    pub fn program(&self) -> &[u8] {
+        &self.program.numbers
+    }
+
+    pub fn output(&self) -> &[u64] {
+        &self.output
+    }
+}
+
+pub use common::ParseError;
+
+pub fn try_parse_input(input: &str) -> Result<Computer, ParseError> {
+    let error = || ParseError("expected three registers, a blank line and a program".to_string());
+    let (registers, program) = input.split_once("\n\n").ok_or_else(error)?;
+    let mut register_lines = registers.lines().map(|line| {
+        let (_, value) = line.split_once(": ").ok_or_else(error)?;
+        value
+            .parse()
+            .map_err(|_| ParseError(format!("{:?} isn't a register value", value)))
+    });
+    let mut next_register = || register_lines.next().ok_or_else(error)?;
+    let registers = (next_register()?, next_register()?, next_register()?);
+
+    let (_, program) = program.split_once(": ").ok_or_else(error)?;
+    let numbers = program
+        .trim()
+        .split(",")
+        .map(|n| {
+            n.parse()
+                .map_err(|_| ParseError(format!("{:?} isn't part of a program", n)))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Computer::new(numbers, registers)
+}
+
+pub fn parse_input(input: &str) -> Computer {
+    try_parse_input(input).unwrap()
+}
+
+pub fn part_1(input: &Computer) -> String {
+    let output = input.run();
+    output
+        .iter()
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub fn part_2(input: &Computer) -> u64 {
+    // my program: 2,4,1,3,7,5,4,0,1,3,0,3,5,5,3,0
+    // this program is:
+    // let b = a & 111;     2,4   b == final three digits of a
+    // b ^= 3;              1,3   (*) twiddle b somehow (still 0 <= b <= 7)
+    // c = a >> b;          7,5   c == a shifted right between 0 and 7 places
+    // b ^= c;              4,0   (**) twiddle b somehow with c
+    // b ^= 3;              1,3   twiddle b somehow (cancels previous ^= 3)
+    // a >>= 3;             0,3   (***) shift a right by three, loses last three digits
+    // print b % 8          5,5   print number formed by last three digits of b
+    // if a != 0 repeat     3,0   go back to start
+    // so:
+    // each loop iteration we print one digit, and then knock the final three digits off a.
+    // by (*) b is between 0 and 7 inclusive
+    // c then shifts this many steps - and we will then use the final three digits of what's left to ^ with b
+    // so the final 10 binary digits of a are relevant each loop
+
+    // the rest of the program does stuff to b and prints its final three digits - but note:
+    // only the last ten (binary) digits of a are relevant this loop
+
+    // after this loop we knock three digits off a and repeat - which means the next loop's final 7 digits
+    // of 10 need to match this loop's first 7 digits of 10 (for a)
+
+    // figure out the results of all possible 'last 10 digits of a' - we can then lift these up and combine
+    // them into the real a
+    let mut result_map = HashMap::new();
+    for possible_a in 0..=1023u64 {
+        let computer = Computer {
+            registers: Registers {
+                a: possible_a,
+                b: 0,
+                c: 0,
+            },
+            output: Vec::new(),
+            instruction_pointer: 0,
+            program: input.program.clone(),
+        };
+        let output = computer.run();
+        result_map
+            .entry(output[0])
+            .or_insert(BTreeSet::new())
+            .insert(possible_a);
+    }
+
+    let mut possible_answers = HashSet::new();
+    let things_to_print = input
+        .program
+        .numbers
+        .iter()
+        .map(|n| *n as u64)
+        .collect::<Vec<_>>();
+
+    // go through, figure out for each digit what a values would work to print that
+    for (magnitude, digit) in things_to_print.iter().enumerate() {
+        let answers_for_this_digit = result_map.get(digit).unwrap();
+        if magnitude == 0 {
+            for a in answers_for_this_digit {
+                possible_answers.insert(*a);
+            }
+        } else {
+            // if we're not on the first digit, we need to figure out what values of a would cause the previous digits
+            // to be printed and also for this digit to be printed next
+            // so we know that we want a to be ending .....ddddddd for this digit to be printed next
+            // and we know what possible ...ddddxxx... values would have caused previous values to be printed
+            let mut new_possible_answers = HashSet::new();
+            // the values of a which cause the previous digits to be printed - we need to shift these left
+            for old_possibility in possible_answers {
+                let ignoring_end = old_possibility >> (3 * magnitude);
+                for extension in answers_for_this_digit {
+                    // the values of 'last 10 digits of a' which would cause this digit to be printed next -
+                    // we need to keep all possibilities where the first seven (binary) digits of this extension
+                    // match the last seven (binary) digits of the old possibility for previous output
+                    if (*extension ^ ignoring_end) % 128 == 0 {
+                        // and then combine them by overlapping those digits
+                        let new_possibility = (*extension << (3 * magnitude)) | old_possibility;
+                        new_possible_answers.insert(new_possibility);
+                    }
+                }
+            }
+
+            possible_answers = new_possible_answers;
+        }
+    }
+
+    // and we have to have stopped after this point precisely:
+    // if there are any digits left after this they would lead to extra output,
+    // and if the first three digits of a are zeroes then they wouldn't print the final output value
+    possible_answers.retain(|answer| {
+        answer >> (3 * things_to_print.len()) == 0
+            && answer >> (3 * (things_to_print.len() - 1)) != 0
+    });
+
+    possible_answers.into_iter().min().unwrap()
+}
+
+#[test]
+pub fn test() {
+    let input = "Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0
+";
+    let input = parse_input(input);
+
+    assert_eq!(part_1(&input), "4,6,3,5,6,3,5,2,1,0");
+    // no clue how to test part 2 here
+}
+
+#[test]
+pub fn test_1() {
+    let computer = Computer {
+        registers: Registers { a: 0, b: 0, c: 9 },
+        program: Program {
+            numbers: vec![2, 6],
+        },
+        instruction_pointer: 0,
+        output: Vec::new(),
+    };
+
+    let new_state = computer.step();
+
+    assert_eq!(
+        new_state,
+        Some(Computer {
+            registers: Registers { a: 0, b: 1, c: 9 },
+            program: Program {
+                numbers: vec![2, 6]
+            },
+            instruction_pointer: 2,
+            output: Vec::new(),
+        })
+    );
+
+    let newer_state = new_state.unwrap().step();
+    assert_eq!(newer_state, None);
+}
+
+#[test]
+pub fn test_2() {
+    let computer = Computer {
+        registers: Registers { a: 10, b: 0, c: 0 },
+        program: Program {
+            numbers: vec![5, 0, 5, 1, 5, 4],
+        },
+        instruction_pointer: 0,
+        output: Vec::new(),
+    };
+
+    let output = computer.run();
+
+    assert_eq!(output, vec![0, 1, 2]);
+}
+
+#[test]
+pub fn test_3() {
+    let computer = Computer {
+        registers: Registers {
+            a: 2024,
+            b: 0,
+            c: 0,
+        },
+        program: Program {
+            numbers: vec![0, 1, 5, 4, 3, 0],
+        },
+        instruction_pointer: 0,
+        output: Vec::new(),
+    };
+
+    let output = computer.run();
+
+    assert_eq!(output, vec![4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]);
+}
+
+#[test]
+pub fn test_run_for() {
+    // jumps back to the start for as long as a isn't zero
+    let computer = Computer::new(vec![3, 0], (1, 0, 0)).unwrap();
+    assert_eq!(computer.run_for(100), Err(VmError::StepLimit(100)));
+
+    let computer = Computer::new(vec![0, 7], (1, 0, 0)).unwrap();
+    assert_eq!(
+        computer.run_for(100),
+        Err(VmError::ReservedOperand {
+            instruction_pointer: 0
+        })
+    );
+
+    assert!(Computer::new(vec![8], (0, 0, 0)).is_err());
+    assert_eq!(Computer::new(vec![], (0, 0, 0)).unwrap().run_for(1), Ok(vec![]));
+    assert!(try_parse_input("Register A: x").is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+pub fn test_json_round_trip() {
+    let input = "Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0
+";
+    let computer = parse_input(input).step().unwrap();
+    let json = serde_json::to_string(&computer).unwrap();
+    assert_eq!(serde_json::from_str::<Computer>(&json).unwrap(), computer);
+
+    // nothing the parser would have let through
+    let computer = |numbers: &str, instruction_pointer: usize| {
+        format!(
+            r#"{{"program":{{"numbers":{}}},"instruction_pointer":{},"registers":{{"a":1,"b":0,"c":0}},"output":[]}}"#,
+            numbers, instruction_pointer
+        )
+    };
+    assert!(serde_json::from_str::<Computer>(&computer("[0,1,5,4]", 7)).is_ok());
+    assert!(serde_json::from_str::<Computer>(&computer("[0,9]", 0)).is_err());
+    assert!(serde_json::from_str::<Computer>(&computer("[0,1]", usize::MAX)).is_err());
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Input {
+    towels: Vec<String>,
+    designs: Vec<String>,
+}
+
+pub use common::ParseError;
+
+pub fn try_parse_input(input: &str) -> Result<Input, ParseError> {
+    let Some((towels_part, designs_part)) = input.split_once("\n\n") else {
+        return Err(ParseError("expected a blank line between the towels and the designs".to_string()));
+    };
+    let towels = towels_part
+        .split(",")
+        .map(|t| t.trim().to_string())
+        .collect();
+    let designs = designs_part
+        .trim()
+        .lines()
+        .map(|line| line.to_string())
+        .collect();
+    Ok(Input { towels, designs })
+}
+
+pub fn parse_input(input: &str) -> Input {
+    try_parse_input(input).unwrap()
+}
+
+pub fn ways_to_make(design: &str, towels: &[String], cache: &mut HashMap<String, usize>) -> usize {
+    if let Some(known_result) = cache.get(design) {
+        return *known_result;
+    }
+
+    if design.is_empty() {
+        return 1;
+    }
+
+    let ways = towels
+        .iter()
+        .filter(|&t| design.starts_with(t))
+        .map(|t| ways_to_make(&design[t.len()..], towels, cache))
+        .sum();
+    cache.insert(design.to_string(), ways);
+    ways
+}
+
+pub fn part_1(input: &Input) -> usize {
+    let mut cache = HashMap::new();
+    input
+        .designs
+        .iter()
+        .filter(|d| ways_to_make(d, &input.towels, &mut cache) > 0)
+        .count()
+}
+
+pub fn part_2(input: &Input) -> usize {
+    let mut cache = HashMap::new();
+    input
+        .designs
+        .iter()
+        .map(|d| ways_to_make(d, &input.towels, &mut cache))
+        .sum()
+}
+
+#[test]
+pub fn test() {
+    let input = "r, wr, b, g, bwu, rb, gb, br
+
+brwrr
+bggr
+gbbr
+rrbgbr
+ubwu
+bwurrg
+brgr
+bbrgwb
+";
+    let input = parse_input(input);
+
+    assert_eq!(part_1(&input), 6);
+    assert_eq!(part_2(&input), 16);
+}
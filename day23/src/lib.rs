@@ -0,0 +1,213 @@
+use std::collections::{HashMap, HashSet};
+use itertools::Itertools;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Graph {
+    vertices: HashSet<String>,
+    neighbours: HashMap<String, HashSet<String>>,
+}
+
+impl Graph {
+    pub fn has_edge(&self, a: &str, b: &str) -> bool {
+        self.neighbours.get(a).is_some_and(|n| n.contains(b))
+    }
+}
+
+pub fn find_cliques(graph: &Graph) -> Vec<(&str, &str, &str)> {
+    let mut cliques = Vec::new();
+    for c in graph.vertices.iter().combinations(3) {
+        let mut nodes = c.clone();
+        nodes.sort();
+        if graph.has_edge(nodes[0], nodes[1]) && graph.has_edge(nodes[0], nodes[2]) && graph.has_edge(nodes[1], nodes[2]) {
+            cliques.push((nodes[0].as_str(), nodes[1].as_str(), nodes[2].as_str()));
+        }
+
+    }
+
+    cliques
+}
+
+pub fn find_maximal_cliques(graph: &Graph) -> Vec<Vec<&str>> {
+    let vertices = graph.vertices.iter().map(|v| v.as_str()).collect();
+    let mut found_cliques = bron_kerbosch(graph, HashSet::new(), vertices, HashSet::new());
+
+    found_cliques.sort_by_key(|s| s.len());
+    found_cliques.iter().map(|s| {
+        let mut s = s.iter().copied().collect::<Vec<_>>();
+        s.sort();
+        s
+    }).collect()
+
+}
+
+pub fn bron_kerbosch<'a>(graph: &'a Graph, r: HashSet<&'a str>, p: HashSet<&'a str>, x: HashSet<&'a str>) -> Vec<HashSet<&'a str>> {
+    if p.is_empty() && x.is_empty() {
+        return vec![r.clone()];
+    }
+
+    let mut cliques = Vec::new();
+    let mut p2 = p.clone();
+    let mut x2 = x.clone();
+    for v in p {
+        let mut r2 = r.clone();
+        r2.insert(v);
+        let neighbours = graph.neighbours.get(v).unwrap();
+        let restricted_p = p2.iter().filter(|n| neighbours.contains(**n)).copied().collect::<HashSet<_>>();
+        let restricted_x = x2.iter().filter(|n| neighbours.contains(**n)).copied().collect::<HashSet<_>>();
+        let res = bron_kerbosch(graph, r2, restricted_p, restricted_x);
+        cliques.extend(res);
+        p2.remove(v);
+        x2.insert(v);
+    }
+
+    cliques
+}
+
+// every triangle is part of some maximal clique, so rather than trying every
+// triple of vertices, take the triples out of each maximal clique
+pub fn find_cliques_from_maximal(graph: &Graph) -> Vec<(&str, &str, &str)> {
+    let mut cliques = HashSet::new();
+    for clique in find_maximal_cliques(graph) {
+        for c in clique.into_iter().combinations(3) {
+            cliques.insert((c[0], c[1], c[2]));
+        }
+    }
+
+    cliques.into_iter().collect()
+}
+
+pub fn part_1(graph: &Graph) -> usize {
+    count_t_cliques(find_cliques(graph))
+}
+
+pub fn part_1_bron_kerbosch(graph: &Graph) -> usize {
+    count_t_cliques(find_cliques_from_maximal(graph))
+}
+
+fn count_t_cliques(cliques: Vec<(&str, &str, &str)>) -> usize {
+    let mut answer = 0;
+    for (c1, c2, c3) in cliques {
+        if c1.starts_with('t') || c2.starts_with('t') || c3.starts_with('t') {
+            answer += 1;
+        }
+    }
+
+    answer
+}
+
+pub fn part_2(graph: &Graph) -> String {
+    let cliques = find_maximal_cliques(graph);
+    let best = cliques.last().unwrap();
+    best.join(",")
+}
+
+pub use common::ParseError;
+
+pub fn try_parse_input(input: &str) -> Result<Graph, ParseError> {
+    let mut vertices = HashSet::new();
+    let mut neighbours = HashMap::new();
+    for (number, line) in input.lines().enumerate() {
+        let Some((left, right)) = line.split_once("-") else {
+            return Err(ParseError(format!("line {}: connections look like kh-tc", number + 1)));
+        };
+        vertices.insert(left.to_string());
+        vertices.insert(right.to_string());
+        neighbours.entry(left.to_string()).or_insert(HashSet::new()).insert(right.to_string());
+        neighbours.entry(right.to_string()).or_insert(HashSet::new()).insert(left.to_string());
+    }
+
+    Ok(Graph { vertices, neighbours })
+}
+
+pub fn parse_input(input: &str) -> Graph {
+    try_parse_input(input).unwrap()
+}
+
+#[test]
+pub fn test() {
+    let input = r#"kh-tc
+qp-kh
+de-cg
+ka-co
+yn-aq
+qp-ub
+cg-tb
+vc-aq
+tb-ka
+wh-tc
+yn-cg
+kh-ub
+ta-co
+de-co
+tc-td
+tb-wq
+wh-td
+ta-ka
+td-qp
+aq-cg
+wq-ub
+ub-vc
+de-ta
+wq-aq
+wq-vc
+wh-yn
+ka-de
+kh-ta
+co-tc
+wh-qp
+tb-vc
+td-yn"#;
+
+    let graph = parse_input(input);
+    let cliques = find_cliques(&graph);
+    assert_eq!(cliques.len(), 12);
+    assert_eq!(part_1(&graph), 7);
+    assert_eq!(find_cliques_from_maximal(&graph).len(), 12);
+    assert_eq!(part_1_bron_kerbosch(&graph), 7);
+    // assert_eq!(next, vec![15887950, 16495136, 527345, 704524, 1553684, 12683156, 11100544, 12249484, 7753432, 5908254])
+}
+
+#[cfg(feature = "serde")]
+#[test]
+pub fn test_json_round_trip() {
+    let input = r#"kh-tc
+qp-kh
+de-cg
+ka-co
+yn-aq
+qp-ub
+cg-tb
+vc-aq
+tb-ka
+wh-tc
+yn-cg
+kh-ub
+ta-co
+de-co
+tc-td
+tb-wq
+wh-td
+ta-ka
+td-qp
+aq-cg
+wq-ub
+ub-vc
+de-ta
+wq-aq
+wq-vc
+wh-yn
+ka-de
+kh-ta
+co-tc
+wh-qp
+tb-vc
+td-yn"#;
+
+    let graph = parse_input(input);
+    let json = serde_json::to_string(&graph).unwrap();
+    let reloaded: Graph = serde_json::from_str(&json).unwrap();
+    assert_eq!(reloaded, graph);
+    assert!(reloaded.has_edge("qp", "kh") && reloaded.has_edge("kh", "qp"));
+    assert_eq!(part_2(&reloaded), "co,de,ka,ta");
+}
@@ -0,0 +1,123 @@
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Input {
+    keys: Vec<Vec<usize>>,
+    locks: Vec<Vec<usize>>,
+    height: usize,
+}
+
+pub use common::ParseError;
+
+pub fn try_parse_input(input: &str) -> Result<Input, ParseError> {
+    let mut keys = Vec::new();
+    let mut locks = Vec::new();
+    let mut height = 0;
+
+    for (number, chunk) in input.split("\n\n").enumerate() {
+        let error = |message: &str| ParseError(format!("schematic {}: {}", number + 1, message));
+        let lines = chunk.lines().collect::<Vec<_>>();
+        if lines.len() < 2 {
+            return Err(error("too short to be a key or a lock"));
+        }
+        if height == 0 {
+            height = lines.len() - 2;
+        }
+        if lines.len() != height + 2 {
+            return Err(error("not the same height as the others"));
+        }
+
+        let top = lines[0];
+        let bottom = lines[lines.len() - 1];
+
+        let mut object = vec![0, 0, 0, 0, 0];
+        for pin_row in &lines[1..=height] {
+            for (index, c) in pin_row.chars().enumerate() {
+                if c == '#' {
+                    *object.get_mut(index).ok_or_else(|| error("wider than five pins"))? += 1;
+                }
+            }
+        }
+
+        if top.chars().all(|c| c == '#') && bottom.chars().all(|c| c == '.') {
+            // this is a lock
+            locks.push(object);
+        } else if bottom.chars().all(|c| c == '#') && top.chars().all(|c| c == '.') {
+            // this is a key
+            keys.push(object);
+        } else {
+            return Err(error("neither a key nor a lock"));
+        }
+    }
+
+    Ok(Input {
+        keys,
+        locks,
+        height,
+    })
+}
+
+pub fn parse_input(input: &str) -> Input {
+    try_parse_input(input).unwrap()
+}
+
+pub fn part_1(input: &Input) -> usize {
+    input
+        .keys
+        .iter()
+        .flat_map(|k| {
+            input
+                .locks
+                .iter()
+                .map(move |l| l.iter().zip(k).map(|(lp, kp)| lp + kp).collect::<Vec<_>>())
+        })
+        .filter(|pins| pins.iter().all(|p| *p <= input.height))
+        .count()
+}
+
+#[test]
+pub fn test_small() {
+    let input = r#"#####
+.####
+.####
+.####
+.#.#.
+.#...
+.....
+
+#####
+##.##
+.#.##
+...##
+...#.
+...#.
+.....
+
+.....
+#....
+#....
+#...#
+#.#.#
+#.###
+#####
+
+.....
+.....
+#.#..
+###..
+###.#
+###.#
+#####
+
+.....
+.....
+.....
+#....
+#.#..
+#.#.#
+#####
+"#;
+
+    let graph = parse_input(input);
+    println!("{:?}", graph);
+    assert_eq!(part_1(&graph), 3);
+}
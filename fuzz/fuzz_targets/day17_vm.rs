@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// the first 24 bytes are registers a, b and c, and the rest is the program - with
+// each byte cut down to the three bits an instruction or operand can hold
+fuzz_target!(|data: &[u8]| {
+    let Some((registers, program)) = data.split_first_chunk::<24>() else {
+        return;
+    };
+    let register = |i: usize| u64::from_le_bytes(registers[i * 8..(i + 1) * 8].try_into().unwrap());
+    let program = program.iter().map(|b| b & 0b111).collect();
+
+    let computer = day17::Computer::new(program, (register(0), register(1), register(2))).unwrap();
+    // every instruction either halts, jumps or moves on, so a program can loop forever -
+    // but it has to give up cleanly when it does
+    let _ = computer.run_for(10_000);
+});
@@ -0,0 +1,279 @@
+use pyo3::exceptions::{PyKeyError, PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use runner::{Answer, Model, SolveError};
+
+// a parsed puzzle input - only the day that parsed it can solve it
+#[pyclass(frozen)]
+pub struct Parsed {
+    #[pyo3(get)]
+    day: u8,
+    model: Model,
+}
+
+impl Parsed {
+    fn check_day(&self, day: u8) -> PyResult<()> {
+        if self.day != day {
+            return Err(PyTypeError::new_err(format!(
+                "expected input parsed by day {}, got day {}",
+                day, self.day
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn model<T: 'static>(&self, day: u8) -> PyResult<&T> {
+        self.check_day(day)?;
+        Ok(runner::model(&self.model))
+    }
+}
+
+#[pymethods]
+impl Parsed {
+    fn __repr__(&self) -> String {
+        format!("<parsed input for day {}>", self.day)
+    }
+}
+
+fn to_py_err(error: SolveError) -> PyErr {
+    match error {
+        SolveError::UnknownPuzzle { .. } => PyKeyError::new_err(error.to_string()),
+        SolveError::Parse(_) => PyValueError::new_err(error.to_string()),
+        SolveError::Panicked(_) | SolveError::TimedOut(_) => {
+            PyRuntimeError::new_err(error.to_string())
+        }
+    }
+}
+
+fn answer_to_py(py: Python<'_>, answer: Answer) -> PyResult<PyObject> {
+    Ok(match answer {
+        Answer::Number(n) => n.into_pyobject(py)?.into_any().unbind(),
+        Answer::Text(t) => t.into_pyobject(py)?.into_any().unbind(),
+    })
+}
+
+#[pyfunction]
+pub fn parse_input(day: u8, input: &str) -> PyResult<Parsed> {
+    let (entry, _) = runner::find(day, 1)
+        .ok_or_else(|| PyKeyError::new_err(format!("there is no solver for day {}", day)))?;
+    let model = runner::parse(entry, input).map_err(to_py_err)?;
+    Ok(Parsed { day, model })
+}
+
+#[pyfunction]
+pub fn solve(py: Python<'_>, input: &Parsed, part: u8) -> PyResult<PyObject> {
+    let (_, part) = runner::find(input.day, part)
+        .ok_or(SolveError::UnknownPuzzle {
+            day: input.day,
+            part,
+        })
+        .map_err(to_py_err)?;
+    let answer = py
+        .allow_threads(|| runner::run_part(part, &input.model))
+        .map_err(to_py_err)?;
+    answer_to_py(py, answer)
+}
+
+macro_rules! day_module {
+    ($name:ident, $day:literal) => {
+        mod $name {
+            use super::Parsed;
+            use pyo3::prelude::*;
+
+            #[pyfunction]
+            pub fn parse_input(input: &str) -> PyResult<Parsed> {
+                super::parse_input($day, input)
+            }
+
+            #[pyfunction]
+            pub fn part_1(py: Python<'_>, input: &Parsed) -> PyResult<PyObject> {
+                input.check_day($day)?;
+                super::solve(py, input, 1)
+            }
+
+            #[pyfunction]
+            pub fn part_2(py: Python<'_>, input: &Parsed) -> PyResult<PyObject> {
+                input.check_day($day)?;
+                super::solve(py, input, 2)
+            }
+
+            pub fn module<'py>(parent: &Bound<'py, PyModule>) -> PyResult<Bound<'py, PyModule>> {
+                let module = PyModule::new(parent.py(), stringify!($name))?;
+                module.add_function(wrap_pyfunction!(parse_input, &module)?)?;
+                module.add_function(wrap_pyfunction!(part_1, &module)?)?;
+                module.add_function(wrap_pyfunction!(part_2, &module)?)?;
+                Ok(module)
+            }
+        }
+    };
+}
+
+day_module!(day01, 1);
+day_module!(day02, 2);
+day_module!(day03, 3);
+day_module!(day04, 4);
+day_module!(day05, 5);
+day_module!(day06, 6);
+day_module!(day07, 7);
+day_module!(day08, 8);
+day_module!(day09, 9);
+day_module!(day10, 10);
+day_module!(day11, 11);
+day_module!(day12, 12);
+day_module!(day13, 13);
+day_module!(day14, 14);
+day_module!(day15, 15);
+day_module!(day16, 16);
+day_module!(day17, 17);
+day_module!(day18, 18);
+day_module!(day19, 19);
+day_module!(day20, 20);
+day_module!(day21, 21);
+day_module!(day22, 22);
+day_module!(day23, 23);
+day_module!(day24, 24);
+day_module!(day25, 25);
+
+fn vm_error(error: ::day17::VmError) -> PyErr {
+    PyValueError::new_err(error.to_string())
+}
+
+// the day 17 machine, one instruction at a time
+#[pyclass(frozen, name = "Computer")]
+#[derive(Clone)]
+pub struct Computer(::day17::Computer);
+
+#[pymethods]
+impl Computer {
+    #[new]
+    fn new(input: &str) -> PyResult<Self> {
+        let parsed = parse_input(17, input)?;
+        Ok(Self(parsed.model::<::day17::Computer>(17)?.clone()))
+    }
+
+    // returns None once the program has halted
+    fn step(&self) -> PyResult<Option<Computer>> {
+        let next = self.0.try_step().map_err(vm_error)?;
+        Ok(next.map(Computer))
+    }
+
+    // gives up with a ValueError rather than hanging on a program that never halts, and lets
+    // go of the GIL while it runs
+    #[pyo3(signature = (max_steps = 10_000_000))]
+    fn run(&self, py: Python<'_>, max_steps: usize) -> PyResult<Vec<u64>> {
+        py.allow_threads(|| self.0.run_for(max_steps))
+            .map_err(vm_error)
+    }
+
+    #[getter]
+    fn registers(&self) -> (u64, u64, u64) {
+        self.0.registers()
+    }
+
+    #[getter]
+    fn instruction_pointer(&self) -> usize {
+        self.0.instruction_pointer()
+    }
+
+    #[getter]
+    fn program(&self) -> Vec<u8> {
+        self.0.program().to_vec()
+    }
+
+    #[getter]
+    fn output(&self) -> Vec<u64> {
+        self.0.output().to_vec()
+    }
+
+    fn __repr__(&self) -> String {
+        let (a, b, c) = self.0.registers();
+        format!(
+            "Computer(a={}, b={}, c={}, ip={}, output={:?})",
+            a,
+            b,
+            c,
+            self.0.instruction_pointer(),
+            self.0.output()
+        )
+    }
+}
+
+#[pyfunction]
+fn find_cliques(input: &Parsed) -> PyResult<Vec<(String, String, String)>> {
+    let graph = input.model::<::day23::Graph>(23)?;
+    Ok(::day23::find_cliques(graph)
+        .into_iter()
+        .map(|(a, b, c)| (a.to_string(), b.to_string(), c.to_string()))
+        .collect())
+}
+
+// sorted smallest first, so the answer to part 2 is the last one
+#[pyfunction]
+fn find_maximal_cliques(input: &Parsed) -> PyResult<Vec<Vec<String>>> {
+    let graph = input.model::<::day23::Graph>(23)?;
+    Ok(::day23::find_maximal_cliques(graph)
+        .into_iter()
+        .map(|clique| clique.into_iter().map(|v| v.to_string()).collect())
+        .collect())
+}
+
+// the value on every wire once the circuit has settled
+#[pyfunction]
+fn calculate(input: &Parsed) -> PyResult<std::collections::HashMap<String, bool>> {
+    Ok(::day24::calculate(input.model::<::day24::Input>(24)?))
+}
+
+#[pymodule]
+fn aoc2024(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Parsed>()?;
+    m.add_function(wrap_pyfunction!(parse_input, m)?)?;
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+
+    let sys_modules = m.py().import("sys")?.getattr("modules")?;
+    let days = [
+        day01::module(m)?,
+        day02::module(m)?,
+        day03::module(m)?,
+        day04::module(m)?,
+        day05::module(m)?,
+        day06::module(m)?,
+        day07::module(m)?,
+        day08::module(m)?,
+        day09::module(m)?,
+        day10::module(m)?,
+        day11::module(m)?,
+        day12::module(m)?,
+        day13::module(m)?,
+        day14::module(m)?,
+        day15::module(m)?,
+        day16::module(m)?,
+        day17::module(m)?,
+        day18::module(m)?,
+        day19::module(m)?,
+        day20::module(m)?,
+        day21::module(m)?,
+        day22::module(m)?,
+        day23::module(m)?,
+        day24::module(m)?,
+        day25::module(m)?,
+    ];
+
+    for module in days {
+        let name = module.name()?;
+        match name.to_str()? {
+            "day17" => module.add_class::<Computer>()?,
+            "day23" => {
+                module.add_function(wrap_pyfunction!(find_cliques, &module)?)?;
+                module.add_function(wrap_pyfunction!(find_maximal_cliques, &module)?)?;
+            }
+            "day24" => module.add_function(wrap_pyfunction!(calculate, &module)?)?,
+            _ => {}
+        }
+
+        // so that `from aoc2024.day17 import Computer` works as well as attribute access
+        sys_modules.set_item(format!("aoc2024.{}", name), &module)?;
+        m.add_submodule(&module)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+import pytest
+
+import aoc2024
+from aoc2024 import day01, day02, day23, day24
+from aoc2024.day17 import Computer
+
+DAY01 = """3   4
+4   3
+2   5
+1   3
+3   9
+3   3
+"""
+
+DAY17 = """Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0"""
+
+DAY23 = """ka-co
+ta-co
+de-co
+ta-ka
+de-ta
+ka-de
+"""
+
+DAY24 = """x00: 1
+x01: 1
+y00: 0
+y01: 1
+
+x00 AND y00 -> z00
+x01 XOR y01 -> z01
+"""
+
+
+def test_parts():
+    parsed = day01.parse_input(DAY01)
+    assert day01.part_1(parsed) == 11
+    assert day01.part_2(parsed) == 31
+    assert aoc2024.solve(parsed, 2) == 31
+
+
+def test_errors():
+    with pytest.raises(ValueError):
+        aoc2024.parse_input(5, "not a rule")
+    with pytest.raises(KeyError):
+        aoc2024.parse_input(26, "")
+    with pytest.raises(TypeError):
+        day02.part_1(day01.parse_input(DAY01))
+
+
+def test_computer():
+    computer = Computer(DAY17)
+    assert computer.registers == (729, 0, 0)
+    assert computer.run() == [4, 6, 3, 5, 6, 3, 5, 2, 1, 0]
+
+    stepped = computer.step()
+    assert stepped.registers == (364, 0, 0)
+    assert stepped.instruction_pointer == 2
+    # stepping returns a new machine, the original is left alone
+    assert computer.instruction_pointer == 0
+
+
+def test_computer_errors():
+    reserved = Computer("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 0,7")
+    with pytest.raises(ValueError):
+        reserved.step()
+    with pytest.raises(ValueError):
+        reserved.run()
+
+    # jumps back to the start for as long as A isn't 0, which is forever
+    looping = Computer("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 3,0")
+    with pytest.raises(ValueError):
+        looping.run(max_steps=1000)
+
+
+def test_cliques():
+    graph = day23.parse_input(DAY23)
+    assert len(day23.find_cliques(graph)) == 4
+    assert day23.find_maximal_cliques(graph)[-1] == ["co", "de", "ka", "ta"]
+
+
+def test_calculate():
+    wires = day24.calculate(day24.parse_input(DAY24))
+    assert wires["z00"] is False
+    assert wires["z01"] is False
@@ -0,0 +1,273 @@
+use crate::{Answer, SolveError, find, parse, run_part};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
+use std::sync::mpsc;
+use std::thread;
+
+// counts are kept per thread, so that whatever else is running (other tests,
+// the server's other requests) doesn't end up in the numbers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Counts {
+    allocations: usize,
+    bytes: usize,
+    live: usize,
+    peak: usize,
+}
+
+thread_local! {
+    static COUNTS: Cell<Counts> = const {
+        Cell::new(Counts {
+            allocations: 0,
+            bytes: 0,
+            live: 0,
+            peak: 0,
+        })
+    };
+}
+
+fn update(f: impl FnOnce(&mut Counts)) {
+    // the thread local may already be gone while the thread is shutting down
+    let _ = COUNTS.try_with(|counts| {
+        let mut c = counts.get();
+        f(&mut c);
+        c.peak = c.peak.max(c.live);
+        counts.set(c);
+    });
+}
+
+pub struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        update(|c| {
+            c.allocations += 1;
+            c.bytes += layout.size();
+            c.live += layout.size();
+        });
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        update(|c| {
+            c.allocations += 1;
+            c.bytes += layout.size();
+            c.live += layout.size();
+        });
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // freeing something another thread allocated can take us below zero
+        update(|c| c.live = c.live.saturating_sub(layout.size()));
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        update(|c| {
+            c.allocations += 1;
+            c.bytes += new_size;
+            c.live = c.live.saturating_sub(layout.size()) + new_size;
+        });
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    pub allocations: usize,
+    pub bytes: usize,
+    // the most heap in use at once, over and above what was already in use beforehand
+    pub peak: usize,
+}
+
+impl Display for AllocStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} bytes allocated, {} bytes peak",
+            self.allocations, self.bytes, self.peak
+        )
+    }
+}
+
+// the allocations made by `f` on this thread
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+    let before = COUNTS.with(|c| c.get());
+    COUNTS.with(|c| {
+        c.set(Counts {
+            peak: before.live,
+            ..before
+        })
+    });
+
+    let result = f();
+
+    let after = COUNTS.with(|c| c.get());
+    // put back the outer peak, in case this measurement is nested inside another
+    COUNTS.with(|c| {
+        c.set(Counts {
+            peak: before.peak.max(after.peak),
+            ..after
+        })
+    });
+
+    let stats = AllocStats {
+        allocations: after.allocations - before.allocations,
+        bytes: after.bytes - before.bytes,
+        peak: after.peak.saturating_sub(before.live),
+    };
+    (result, stats)
+}
+
+// anything left as None isn't checked
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    pub allocations: Option<usize>,
+    pub bytes: Option<usize>,
+    pub peak: Option<usize>,
+}
+
+impl Limits {
+    pub const fn new() -> Self {
+        Self {
+            allocations: None,
+            bytes: None,
+            peak: None,
+        }
+    }
+
+    pub const fn allocations(self, allocations: usize) -> Self {
+        Self {
+            allocations: Some(allocations),
+            ..self
+        }
+    }
+
+    pub const fn bytes(self, bytes: usize) -> Self {
+        Self {
+            bytes: Some(bytes),
+            ..self
+        }
+    }
+
+    pub const fn peak(self, peak: usize) -> Self {
+        Self {
+            peak: Some(peak),
+            ..self
+        }
+    }
+}
+
+impl AllocStats {
+    // a description of each limit that was gone over
+    pub fn exceeded(&self, limits: &Limits) -> Vec<String> {
+        let checks = [
+            ("allocations", self.allocations, limits.allocations),
+            ("bytes allocated", self.bytes, limits.bytes),
+            ("peak bytes", self.peak, limits.peak),
+        ];
+        checks
+            .into_iter()
+            .filter_map(|(name, actual, limit)| {
+                let limit = limit?;
+                (actual > limit).then(|| format!("{} {} over the limit of {}", actual, name, limit))
+            })
+            .collect()
+    }
+
+    pub fn within(&self, limits: &Limits) -> bool {
+        self.exceeded(limits).is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Profile {
+    pub answer: Answer,
+    pub parse: AllocStats,
+    pub solve: AllocStats,
+}
+
+// like `solve`, but counting allocations rather than time
+pub fn profile(day: u8, part: u8, input: &str) -> Result<Profile, SolveError> {
+    let (day, part) = find(day, part).ok_or(SolveError::UnknownPuzzle { day, part })?;
+    let input = input.to_string();
+
+    let (sender, receiver) = mpsc::channel();
+    // a fresh thread, so nothing else has been counted against it
+    thread::spawn(move || {
+        let (model, parse_stats) = measure(|| parse(day, &input));
+        let result = model.and_then(|model| {
+            let (answer, solve_stats) = measure(|| run_part(part, &model));
+            answer.map(|answer| Profile {
+                answer,
+                parse: parse_stats,
+                solve: solve_stats,
+            })
+        });
+        let _ = sender.send(result);
+    });
+
+    receiver
+        .recv_timeout(part.timeout)
+        .unwrap_or(Err(SolveError::TimedOut(part.timeout)))
+}
+
+#[test]
+pub fn test_measure() {
+    let (v, stats) = measure(|| vec![0u64; 1000]);
+    assert_eq!(stats.allocations, 1);
+    assert_eq!(stats.bytes, 8000);
+    assert_eq!(stats.peak, 8000);
+
+    // dropped straight away, so each one is counted but the peak is only one of them
+    let ((), stats) = measure(|| {
+        for _ in 0..10 {
+            drop(std::hint::black_box(vec![0u8; 100]));
+        }
+    });
+    assert_eq!(stats.allocations, 10);
+    assert_eq!(stats.bytes, 1000);
+    assert_eq!(stats.peak, 100);
+
+    // nothing allocated in the middle of something else is lost
+    let (_, outer) = measure(|| measure(|| vec![0u8; 50]));
+    assert_eq!(outer.bytes, 50);
+
+    assert!(stats.within(&Limits::new().allocations(10).peak(100)));
+    assert_eq!(
+        stats.exceeded(&Limits::new().allocations(5).bytes(5000)),
+        vec!["10 allocations over the limit of 5".to_string()]
+    );
+    drop(v);
+}
+
+#[test]
+pub fn test_profile() {
+    let input = "Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0
+";
+    let result = profile(17, 1, input).unwrap();
+    assert_eq!(
+        result.answer,
+        Answer::Text("4,6,3,5,6,3,5,2,1,0".to_string())
+    );
+    assert!(result.parse.allocations > 0);
+    // the machine clones itself, output and all, for every instruction it runs
+    assert!(
+        result
+            .solve
+            .within(&Limits::new().allocations(200).peak(4096))
+    );
+
+    assert!(matches!(
+        profile(5, 1, "not a rule"),
+        Err(SolveError::Parse(_))
+    ));
+}
@@ -0,0 +1,12 @@
+use runner::server::serve;
+use tiny_http::Server;
+
+// usage: server [address], e.g. `server 127.0.0.1:8080`
+fn main() {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    let server = Server::http(&address).unwrap();
+    println!("Listening on http://{}", server.server_addr());
+    serve(server);
+}
@@ -0,0 +1,226 @@
+use crate::{Answer, Solution, SolveError, find, solve};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_DIR: &str = "target/runner-cache";
+
+// fnv-1a - std's hasher isn't guaranteed to give the same answer between releases,
+// which would quietly empty the cache on every toolchain update
+pub fn hash(data: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in data.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub day: u8,
+    pub part: u8,
+    pub input_hash: String,
+    pub version: String,
+}
+
+impl Key {
+    pub fn new(day: u8, part: u8, input: &str) -> Result<Self, SolveError> {
+        let (entry, _) = find(day, part).ok_or(SolveError::UnknownPuzzle { day, part })?;
+        Ok(Self {
+            day,
+            part,
+            input_hash: hash(input),
+            version: entry.version(),
+        })
+    }
+
+    fn file_name(&self) -> String {
+        format!(
+            "day{:02}-part{}-{}-{}.json",
+            self.day, self.part, self.input_hash, self.version
+        )
+    }
+}
+
+// one line of the timing history
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Run {
+    pub day: u8,
+    pub part: u8,
+    pub input_hash: String,
+    pub version: String,
+    pub commit: Option<String>,
+    pub timestamp: u64,
+    pub parse_time_ms: f64,
+    pub solve_time_ms: f64,
+}
+
+impl Run {
+    pub fn new(key: &Key, solution: &Solution) -> Self {
+        Self {
+            day: key.day,
+            part: key.part,
+            input_hash: key.input_hash.clone(),
+            version: key.version.clone(),
+            commit: current_commit(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            parse_time_ms: solution.parse_time.as_secs_f64() * 1000.0,
+            solve_time_ms: solution.solve_time.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+pub fn current_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8(output.stdout).ok()?.trim().to_string())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Cached(Answer),
+    Solved(Solution),
+}
+
+impl Outcome {
+    pub fn answer(&self) -> &Answer {
+        match self {
+            Outcome::Cached(answer) => answer,
+            Outcome::Solved(solution) => &solution.answer,
+        }
+    }
+}
+
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn get(&self, key: &Key) -> Option<Answer> {
+        let json = fs::read_to_string(self.dir.join(key.file_name())).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    pub fn insert(&self, key: &Key, answer: &Answer) -> std::io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(
+            self.dir.join(key.file_name()),
+            serde_json::to_string(answer).unwrap(),
+        )
+    }
+
+    pub fn record(&self, run: &Run) -> std::io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join("history.jsonl"))?;
+        writeln!(file, "{}", serde_json::to_string(run).unwrap())
+    }
+
+    // every timed run of a part, oldest first
+    pub fn history(&self, day: u8, part: u8) -> Vec<Run> {
+        let Ok(history) = fs::read_to_string(self.dir.join("history.jsonl")) else {
+            return Vec::new();
+        };
+
+        history
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Run>(line).ok())
+            .filter(|run| run.day == day && run.part == part)
+            .collect()
+    }
+
+    pub fn solve(&self, day: u8, part: u8, input: &str) -> Result<Outcome, SolveError> {
+        let key = Key::new(day, part, input)?;
+        if let Some(answer) = self.get(&key) {
+            return Ok(Outcome::Cached(answer));
+        }
+
+        self.refresh(day, part, input).map(Outcome::Solved)
+    }
+
+    // solves from scratch whatever is in the cache, and stores the new answer
+    pub fn refresh(&self, day: u8, part: u8, input: &str) -> Result<Solution, SolveError> {
+        let key = Key::new(day, part, input)?;
+        let solution = solve(day, part, input)?;
+
+        // not being able to write the cache shouldn't cost us the answer
+        let written = self
+            .insert(&key, &solution.answer)
+            .and_then(|_| self.record(&Run::new(&key, &solution)));
+        if let Err(e) = written {
+            eprintln!(
+                "could not update the cache in {}: {}",
+                self.dir.display(),
+                e
+            );
+        }
+
+        Ok(solution)
+    }
+}
+
+#[test]
+pub fn test_cache() {
+    let dir = std::env::temp_dir().join(format!("runner-cache-test-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let cache = Cache::new(&dir);
+
+    let input = "3   4
+4   3
+2   5
+1   3
+3   9
+3   3
+";
+    let outcome = cache.solve(1, 1, input).unwrap();
+    assert!(matches!(outcome, Outcome::Solved(_)));
+    assert_eq!(outcome.answer(), &Answer::Number(11));
+    assert_eq!(
+        cache.solve(1, 1, input).unwrap(),
+        Outcome::Cached(Answer::Number(11))
+    );
+
+    // a different input or a change to the solver is a different key
+    assert!(matches!(
+        cache.solve(1, 1, &input.replace('9', "8")).unwrap(),
+        Outcome::Solved(_)
+    ));
+    let mut key = Key::new(1, 1, input).unwrap();
+    assert_eq!(cache.get(&key), Some(Answer::Number(11)));
+    // the shared code and dependencies are in the version too, not just the day's lib.rs
+    assert_ne!(key.version, hash(find(1, 1).unwrap().0.source));
+    key.version = hash("some other solver");
+    assert_eq!(cache.get(&key), None);
+
+    cache.refresh(1, 1, input).unwrap();
+    let history = cache.history(1, 1);
+    assert_eq!(history.len(), 3);
+    assert_eq!(history[0].input_hash, hash(input));
+    assert!(cache.history(1, 2).is_empty());
+
+    assert_eq!(
+        cache.solve(26, 1, input),
+        Err(SolveError::UnknownPuzzle { day: 26, part: 1 })
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,288 @@
+use crate::{Day, Part, Variant, model, parsed};
+use std::collections::HashMap;
+use std::time::Duration;
+
+// the parts that brute force their way through a lot of states
+const SLOW_TIMEOUT: Duration = Duration::from_secs(300);
+
+pub static DAYS: &[Day] = &[
+    Day {
+        day: 1,
+        source: include_str!("../../day01/src/lib.rs"),
+        parse: |input| parsed(day01::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| day01::part_1(model(m)).into()),
+            Part::new(2, |m| day01::part_2(model(m)).into()),
+        ],
+    },
+    Day {
+        day: 2,
+        source: include_str!("../../day02/src/lib.rs"),
+        parse: |input| parsed(day02::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| {
+                day02::part_1(model::<Vec<Vec<i64>>>(m), &day02::SafetyPolicy::default()).into()
+            }),
+            Part::new(2, |m| {
+                let policy = day02::SafetyPolicy::default().with_tolerance(1);
+                day02::part_2(model::<Vec<Vec<i64>>>(m), &policy).into()
+            })
+            .with_variants(&[Variant::new("brute force", |m| {
+                day02::part_2_brute_force(model::<Vec<Vec<i64>>>(m)).into()
+            })]),
+        ],
+    },
+    Day {
+        day: 3,
+        source: include_str!("../../day03/src/lib.rs"),
+        parse: |input| parsed(day03::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| {
+                day03::part_1(model::<Vec<day03::Instruction>>(m)).into()
+            }),
+            Part::new(2, |m| {
+                day03::part_2(model::<Vec<day03::Instruction>>(m)).into()
+            }),
+        ],
+    },
+    Day {
+        day: 4,
+        source: include_str!("../../day04/src/lib.rs"),
+        parse: |input| parsed(day04::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| day04::part_1(model(m)).into()),
+            Part::new(2, |m| day04::part_2(model(m)).into()),
+        ],
+    },
+    Day {
+        day: 5,
+        source: include_str!("../../day05/src/lib.rs"),
+        parse: |input| parsed(day05::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| day05::part_1(model(m)).into()),
+            Part::new(2, |m| day05::part_2(model(m)).into())
+                .with_variants(&[Variant::new("minimal moves", |m| {
+                    day05::part_2_repair(model(m)).into()
+                })]),
+        ],
+    },
+    Day {
+        day: 6,
+        source: include_str!("../../day06/src/lib.rs"),
+        parse: |input| parsed(day06::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| day06::part_1(model(m)).into()),
+            Part::new(2, |m| day06::part_2(model(m)).into())
+                .with_timeout(SLOW_TIMEOUT)
+                .with_variants(&[Variant::new("re-walk", |m| {
+                    day06::part_2_rewalk(model(m)).into()
+                })]),
+        ],
+    },
+    Day {
+        day: 7,
+        source: include_str!("../../day07/src/lib.rs"),
+        parse: |input| parsed(day07::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| {
+                day07::part_1(model::<Vec<day07::Calibration>>(m)).into()
+            }),
+            Part::new(2, |m| {
+                day07::part_2(model::<Vec<day07::Calibration>>(m)).into()
+            })
+            .with_variants(&[Variant::new("forward search", |m| {
+                day07::part_2_forward(model::<Vec<day07::Calibration>>(m)).into()
+            })]),
+        ],
+    },
+    Day {
+        day: 8,
+        source: include_str!("../../day08/src/lib.rs"),
+        parse: |input| parsed(day08::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| day08::part_1(model(m)).into()),
+            Part::new(2, |m| day08::part_2(model(m)).into()),
+        ],
+    },
+    Day {
+        day: 9,
+        source: include_str!("../../day09/src/lib.rs"),
+        parse: |input| parsed(day09::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| {
+                day09::part_1(model::<Vec<day09::DiskEntry>>(m)).into()
+            }),
+            Part::new(2, |m| {
+                day09::part_2(model::<Vec<day09::DiskEntry>>(m)).into()
+            }),
+        ],
+    },
+    Day {
+        day: 10,
+        source: include_str!("../../day10/src/lib.rs"),
+        parse: |input| parsed(day10::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| day10::part_1(model(m)).into()),
+            Part::new(2, |m| day10::part_2(model(m)).into()),
+        ],
+    },
+    Day {
+        day: 11,
+        source: include_str!("../../day11/src/lib.rs"),
+        parse: |input| parsed(day11::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| {
+                day11::run(model::<Vec<u64>>(m), 25, &mut HashMap::new()).into()
+            }),
+            Part::new(2, |m| {
+                day11::run(model::<Vec<u64>>(m), 75, &mut HashMap::new()).into()
+            }),
+        ],
+    },
+    Day {
+        day: 12,
+        source: include_str!("../../day12/src/lib.rs"),
+        parse: |input| parsed(day12::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| day12::part_1(model(m)).into()),
+            Part::new(2, |m| day12::part_2(model(m)).into()),
+        ],
+    },
+    Day {
+        day: 13,
+        source: include_str!("../../day13/src/lib.rs"),
+        parse: |input| parsed(day13::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| {
+                day13::part_1(model::<Vec<day13::ClawMachine>>(m)).into()
+            }),
+            Part::new(2, |m| {
+                day13::part_2(model::<Vec<day13::ClawMachine>>(m)).into()
+            }),
+        ],
+    },
+    Day {
+        day: 14,
+        source: include_str!("../../day14/src/lib.rs"),
+        parse: |input| parsed(day14::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| {
+                day14::part_1(model::<Vec<day14::Robot>>(m), (101, 103)).into()
+            }),
+            Part::new(2, |m| {
+                day14::part_2(model::<Vec<day14::Robot>>(m), (101, 103))
+                    .expect("the robots never made a picture")
+                    .into()
+            })
+            .with_timeout(SLOW_TIMEOUT),
+        ],
+    },
+    Day {
+        day: 15,
+        source: include_str!("../../day15/src/lib.rs"),
+        parse: |input| parsed(day15::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| day15::part_1(model(m)).into()),
+            Part::new(2, |m| day15::part_2(model(m)).into()),
+        ],
+    },
+    Day {
+        day: 16,
+        source: include_str!("../../day16/src/lib.rs"),
+        parse: |input| parsed(day16::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| day16::part_1(model(m)).into()),
+            Part::new(2, |m| day16::part_2(model(m)).into()),
+        ],
+    },
+    Day {
+        day: 17,
+        source: include_str!("../../day17/src/lib.rs"),
+        parse: |input| parsed(day17::try_parse_input(input.trim())),
+        parts: &[
+            Part::new(1, |m| day17::part_1(model(m)).into()),
+            Part::new(2, |m| day17::part_2(model(m)).into()),
+        ],
+    },
+    Day {
+        day: 18,
+        source: include_str!("../../day18/src/lib.rs"),
+        parse: |input| parsed(day18::try_parse_input(input.trim())),
+        parts: &[
+            Part::new(1, |m| {
+                day18::part_1(model::<Vec<day18::Coord>>(m), (70, 70).into(), 1024).into()
+            }),
+            Part::new(2, |m| {
+                day18::part_2(model::<Vec<day18::Coord>>(m), (70, 70).into(), 1024)
+                    .to_string()
+                    .into()
+            }),
+        ],
+    },
+    Day {
+        day: 19,
+        source: include_str!("../../day19/src/lib.rs"),
+        parse: |input| parsed(day19::try_parse_input(input.trim())),
+        parts: &[
+            Part::new(1, |m| day19::part_1(model(m)).into()),
+            Part::new(2, |m| day19::part_2(model(m)).into()),
+        ],
+    },
+    Day {
+        day: 20,
+        source: include_str!("../../day20/src/lib.rs"),
+        parse: |input| parsed(day20::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| day20::part_1(model(m)).into())
+                .with_variants(&[Variant::new("within radius", |m| {
+                    day20::part_1_within(model(m)).into()
+                })]),
+            Part::new(2, |m| day20::part_2(model(m)).into()).with_timeout(SLOW_TIMEOUT),
+        ],
+    },
+    Day {
+        day: 21,
+        source: include_str!("../../day21/src/lib.rs"),
+        parse: |input| parsed(day21::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| day21::part_1(model(m)).into()),
+            Part::new(2, |m| day21::part_2(model(m)).into()),
+        ],
+    },
+    Day {
+        day: 22,
+        source: include_str!("../../day22/src/lib.rs"),
+        parse: |input| parsed(day22::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| day22::part_1(model::<Vec<day22::Buyer>>(m)).into()),
+            Part::new(2, |m| day22::part_2(model::<Vec<day22::Buyer>>(m)).into()),
+        ],
+    },
+    Day {
+        day: 23,
+        source: include_str!("../../day23/src/lib.rs"),
+        parse: |input| parsed(day23::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| day23::part_1(model(m)).into())
+                .with_variants(&[Variant::new("bron kerbosch", |m| {
+                    day23::part_1_bron_kerbosch(model(m)).into()
+                })]),
+            Part::new(2, |m| day23::part_2(model(m)).into()),
+        ],
+    },
+    Day {
+        day: 24,
+        source: include_str!("../../day24/src/lib.rs"),
+        parse: |input| parsed(day24::try_parse_input(input)),
+        parts: &[
+            Part::new(1, |m| day24::part_1(model(m)).into()),
+            Part::new(2, |m| day24::part_2(model(m)).into()),
+        ],
+    },
+    Day {
+        day: 25,
+        source: include_str!("../../day25/src/lib.rs"),
+        parse: |input| parsed(day25::try_parse_input(input)),
+        parts: &[Part::new(1, |m| day25::part_1(model(m)).into())],
+    },
+];
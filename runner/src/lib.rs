@@ -0,0 +1,525 @@
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::{Arc, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "alloc-stats")]
+pub mod alloc;
+pub mod cache;
+pub mod days;
+pub mod report;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "tui")]
+pub mod tui;
+
+// the parsed input for a day - each day has its own model type, so the registry
+// keeps them type-erased and each part downcasts back to what it expects
+pub type Model = Box<dyn Any + Send + Sync>;
+
+pub fn parsed<T: Any + Send + Sync, E: Display>(result: Result<T, E>) -> Result<Model, String> {
+    match result {
+        Ok(model) => Ok(Box::new(model)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub fn model<T: 'static>(model: &Model) -> &T {
+    model
+        .downcast_ref()
+        .expect("model was parsed by a different day")
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(t) => write!(f, "{}", t),
+        }
+    }
+}
+
+macro_rules! number_answer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Number(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+number_answer!(i32, u32, i64, u64, usize, i128);
+
+// past i128::MAX it has to be the digits instead
+impl From<u128> for Answer {
+    fn from(n: u128) -> Self {
+        i128::try_from(n)
+            .map(Answer::Number)
+            .unwrap_or_else(|_| Answer::Text(n.to_string()))
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+// written out by hand because serde can't buffer an i128 for an untagged enum
+impl<'de> Deserialize<'de> for Answer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AnswerVisitor;
+
+        impl Visitor<'_> for AnswerVisitor {
+            type Value = Answer;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                write!(f, "a number or a string")
+            }
+
+            fn visit_i64<E: de::Error>(self, n: i64) -> Result<Answer, E> {
+                Ok(Answer::Number(n as i128))
+            }
+
+            fn visit_u64<E: de::Error>(self, n: u64) -> Result<Answer, E> {
+                Ok(Answer::Number(n as i128))
+            }
+
+            fn visit_i128<E: de::Error>(self, n: i128) -> Result<Answer, E> {
+                Ok(Answer::Number(n))
+            }
+
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<Answer, E> {
+                Ok(Answer::Text(s.to_string()))
+            }
+        }
+
+        deserializer.deserialize_any(AnswerVisitor)
+    }
+}
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+// another way of solving a part - kept alongside the default one so that
+// optimisations can be checked against the original
+pub struct Variant {
+    pub name: &'static str,
+    pub solve: fn(&Model) -> Answer,
+}
+
+impl Variant {
+    pub const fn new(name: &'static str, solve: fn(&Model) -> Answer) -> Self {
+        Self { name, solve }
+    }
+}
+
+pub struct Part {
+    pub part: u8,
+    pub solve: fn(&Model) -> Answer,
+    pub timeout: Duration,
+    pub variants: &'static [Variant],
+}
+
+impl Part {
+    pub const fn new(part: u8, solve: fn(&Model) -> Answer) -> Self {
+        Self {
+            part,
+            solve,
+            timeout: DEFAULT_TIMEOUT,
+            variants: &[],
+        }
+    }
+
+    pub const fn with_timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    pub const fn with_variants(self, variants: &'static [Variant]) -> Self {
+        Self { variants, ..self }
+    }
+
+    // the default solver comes first, named "default"
+    pub fn all_variants(&self) -> impl Iterator<Item = (&'static str, fn(&Model) -> Answer)> {
+        std::iter::once(("default", self.solve))
+            .chain(self.variants.iter().map(|v| (v.name, v.solve)))
+    }
+}
+
+pub struct Day {
+    pub day: u8,
+    // the day's solver code, so that cached answers can be thrown away when it changes
+    pub source: &'static str,
+    pub parse: fn(&str) -> Result<Model, String>,
+    pub parts: &'static [Part],
+}
+
+impl Day {
+    pub fn part(&self, part: u8) -> Option<&Part> {
+        self.parts.iter().find(|p| p.part == part)
+    }
+
+    // covers everything a cached answer depends on besides the day itself - the
+    // shared code, the adapters in days.rs, and the locked dependency versions
+    pub fn version(&self) -> String {
+        let mut sources = vec![self.source];
+        sources.extend(SHARED_SOURCES);
+        cache::hash(&sources.join("\0"))
+    }
+}
+
+const SHARED_SOURCES: [&str; 3] = [
+    include_str!("../../common/src/lib.rs"),
+    include_str!("days.rs"),
+    include_str!("../../Cargo.lock"),
+];
+
+pub fn find(day: u8, part: u8) -> Option<(&'static Day, &'static Part)> {
+    let day = days::DAYS.iter().find(|d| d.day == day)?;
+    Some((day, day.part(part)?))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SolveError {
+    UnknownPuzzle { day: u8, part: u8 },
+    Parse(String),
+    Panicked(String),
+    TimedOut(Duration),
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::UnknownPuzzle { day, part } => {
+                write!(f, "there is no solver for day {} part {}", day, part)
+            }
+            SolveError::Parse(message) => write!(f, "could not parse input: {}", message),
+            SolveError::Panicked(message) => write!(f, "solver panicked: {}", message),
+            SolveError::TimedOut(timeout) => write!(f, "solver took longer than {:?}", timeout),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    pub answer: Answer,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+// a parser that panics rather than returning an error is still a parse error
+pub fn parse(day: &Day, input: &str) -> Result<Model, SolveError> {
+    catch_unwind(|| (day.parse)(input))
+        .map_err(panic_message)
+        .and_then(|result| result)
+        .map_err(SolveError::Parse)
+}
+
+pub fn run_part(part: &Part, model: &Model) -> Result<Answer, SolveError> {
+    run_solver(part.solve, model)
+}
+
+fn run_solver(solve: fn(&Model) -> Answer, model: &Model) -> Result<Answer, SolveError> {
+    catch_unwind(AssertUnwindSafe(|| solve(model)))
+        .map_err(|payload| SolveError::Panicked(panic_message(payload)))
+}
+
+pub fn solve(day: u8, part: u8, input: &str) -> Result<Solution, SolveError> {
+    let (day, part) = find(day, part).ok_or(SolveError::UnknownPuzzle { day, part })?;
+    let input = input.to_string();
+
+    let (sender, receiver) = mpsc::channel();
+    // a timed out solver can't be stopped, so it is left to finish in the background
+    thread::spawn(move || {
+        let now = Instant::now();
+        let model = match parse(day, &input) {
+            Ok(model) => model,
+            Err(e) => {
+                let _ = sender.send(Err(e));
+                return;
+            }
+        };
+        let parse_time = now.elapsed();
+
+        let now = Instant::now();
+        let result = run_part(part, &model).map(|answer| Solution {
+            answer,
+            parse_time,
+            solve_time: now.elapsed(),
+        });
+        let _ = sender.send(result);
+    });
+
+    receiver
+        .recv_timeout(part.timeout)
+        .unwrap_or(Err(SolveError::TimedOut(part.timeout)))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Timing {
+    pub name: &'static str,
+    pub result: Result<Answer, SolveError>,
+    pub time: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comparison {
+    pub parse_time: Duration,
+    pub timings: Vec<Timing>,
+}
+
+impl Comparison {
+    // every variant finished and they all came up with the same answer
+    pub fn agrees(&self) -> bool {
+        let mut results = self.timings.iter().map(|t| t.result.as_ref());
+        match results.next() {
+            Some(Ok(first)) => results.all(|r| r == Ok(first)),
+            _ => false,
+        }
+    }
+
+    // how much slower each variant was than the fastest one that succeeded
+    pub fn relative_times(&self) -> Vec<f64> {
+        let fastest = self
+            .timings
+            .iter()
+            .filter(|t| t.result.is_ok())
+            .map(|t| t.time)
+            .min()
+            .unwrap_or_default()
+            .max(Duration::from_nanos(1));
+        self.timings
+            .iter()
+            .map(|t| t.time.as_secs_f64() / fastest.as_secs_f64())
+            .collect()
+    }
+}
+
+// parses once, then runs each variant in turn against the same model - one at a
+// time, so they aren't competing with each other for the cpu
+pub fn compare_part(day: &'static Day, part: &Part, input: &str) -> Result<Comparison, SolveError> {
+    // the parse gets the same timeout as each variant
+    let (sender, receiver) = mpsc::channel();
+    let input = input.to_string();
+    thread::spawn(move || {
+        let now = Instant::now();
+        let model = parse(day, &input);
+        let _ = sender.send((model, now.elapsed()));
+    });
+    let (model, parse_time) = receiver
+        .recv_timeout(part.timeout)
+        .map_err(|_| SolveError::TimedOut(part.timeout))?;
+    let model = Arc::new(model?);
+
+    let mut timings = Vec::new();
+    for (name, solve) in part.all_variants() {
+        let (sender, receiver) = mpsc::channel();
+        let model = model.clone();
+        thread::spawn(move || {
+            let now = Instant::now();
+            let result = run_solver(solve, &model);
+            let _ = sender.send((result, now.elapsed()));
+        });
+
+        let (result, time) = receiver
+            .recv_timeout(part.timeout)
+            .unwrap_or((Err(SolveError::TimedOut(part.timeout)), part.timeout));
+        timings.push(Timing { name, result, time });
+    }
+
+    Ok(Comparison {
+        parse_time,
+        timings,
+    })
+}
+
+pub fn compare(day: u8, part: u8, input: &str) -> Result<Comparison, SolveError> {
+    let (day, part) = find(day, part).ok_or(SolveError::UnknownPuzzle { day, part })?;
+    compare_part(day, part, input)
+}
+
+#[test]
+pub fn test_solve() {
+    let input = "3   4
+4   3
+2   5
+1   3
+3   9
+3   3
+";
+    assert_eq!(solve(1, 1, input).unwrap().answer, Answer::Number(11));
+    assert_eq!(solve(1, 2, input).unwrap().answer, Answer::Number(31));
+
+    let input = "Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0
+";
+    assert_eq!(
+        solve(17, 1, input).unwrap().answer,
+        Answer::Text("4,6,3,5,6,3,5,2,1,0".to_string())
+    );
+}
+
+#[test]
+pub fn test_answer_json() {
+    for answer in [
+        Answer::Number(-7),
+        Answer::Number(1 << 40),
+        Answer::Text("1,2".to_string()),
+    ] {
+        let json = serde_json::to_string(&answer).unwrap();
+        assert_eq!(serde_json::from_str::<Answer>(&json).unwrap(), answer);
+    }
+    assert_eq!(serde_json::to_string(&Answer::Number(3)).unwrap(), "3");
+}
+
+#[test]
+pub fn test_errors() {
+    assert_eq!(
+        solve(25, 2, ""),
+        Err(SolveError::UnknownPuzzle { day: 25, part: 2 })
+    );
+    assert!(matches!(
+        solve(5, 1, "not a rule"),
+        Err(SolveError::Parse(_))
+    ));
+}
+
+// the fuzz targets' seed inputs, cut short and scribbled on - every parser should
+// turn these down with an error rather than a panic
+#[test]
+pub fn test_parse_corpus() {
+    let corpus = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../fuzz/corpus");
+    for day in days::DAYS {
+        let dir = corpus.join(format!("parse_day{:02}", day.day));
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let example = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+            let mut inputs: Vec<String> = example
+                .char_indices()
+                .map(|(i, _)| example[..i].to_string())
+                .collect();
+            for (i, c) in example.char_indices() {
+                for junk in ["", "x", "-", "9", " ", "\n", "99999999999999999999"] {
+                    inputs.push(format!(
+                        "{}{}{}",
+                        &example[..i],
+                        junk,
+                        &example[i + c.len_utf8()..]
+                    ));
+                }
+            }
+
+            for input in inputs {
+                let result = std::panic::catch_unwind(|| (day.parse)(&input));
+                assert!(
+                    result.is_ok(),
+                    "day {} panicked parsing {:?}",
+                    day.day,
+                    input
+                );
+            }
+        }
+    }
+}
+
+#[test]
+pub fn test_compare() {
+    let input = "kh-tc
+qp-kh
+de-cg
+ka-co
+yn-aq
+qp-ub
+cg-tb
+vc-aq
+tb-ka
+wh-tc
+yn-cg
+kh-ub
+ta-co
+de-co
+tc-td
+tb-wq
+wh-td
+ta-ka
+td-qp
+aq-cg
+wq-ub
+ub-vc
+de-ta
+wq-aq
+wq-vc
+wh-yn
+ka-de
+kh-ta
+co-tc
+wh-qp
+tb-vc
+td-yn
+";
+    let comparison = compare(23, 1, input).unwrap();
+    assert!(comparison.timings.len() > 1);
+    assert_eq!(comparison.timings[0].name, "default");
+    assert!(comparison.agrees());
+    assert_eq!(comparison.timings[1].result, Ok(Answer::Number(7)));
+
+    static DAY: Day = Day {
+        day: 0,
+        source: "",
+        parse: |input| Ok(Box::new(input.len())),
+        parts: &[
+            Part::new(1, |m| (*model::<usize>(m)).into()).with_variants(&[
+                Variant::new("off by one", |m| (*model::<usize>(m) + 1).into()),
+                Variant::new("broken", |_| panic!("not implemented")),
+            ]),
+        ],
+    };
+    let comparison = compare_part(&DAY, &DAY.parts[0], "abc").unwrap();
+    assert!(!comparison.agrees());
+    assert_eq!(comparison.timings[1].result, Ok(Answer::Number(4)));
+    assert!(matches!(
+        comparison.timings[2].result,
+        Err(SolveError::Panicked(_))
+    ));
+    assert_eq!(comparison.relative_times().len(), 3);
+
+    static SLOW: Day = Day {
+        day: 0,
+        source: "",
+        parse: |_| loop {
+            thread::sleep(Duration::from_secs(1));
+        },
+        parts: &[Part::new(1, |_| 0.into()).with_timeout(Duration::from_millis(50))],
+    };
+    assert_eq!(
+        compare_part(&SLOW, &SLOW.parts[0], "abc"),
+        Err(SolveError::TimedOut(Duration::from_millis(50)))
+    );
+}
@@ -0,0 +1,216 @@
+use runner::cache::{self, Cache, Outcome};
+use runner::report;
+use std::process::ExitCode;
+
+const USAGE: &str =
+    "usage: runner <run|compare|history|profile|step> <day> <part> [input file] [--no-cache]
+       runner report [output file] [--no-cache]";
+
+fn main() -> ExitCode {
+    // --no-cache solves from scratch, but still stores the new answer and timing
+    let (flags, args): (Vec<_>, Vec<_>) =
+        std::env::args().skip(1).partition(|a| a.starts_with("--"));
+    let use_cache = !flags.iter().any(|f| f == "--no-cache");
+    let cache = Cache::new(cache::DEFAULT_DIR);
+    if args.first().map(String::as_str) == Some("report") {
+        let path = args
+            .get(1)
+            .map(String::as_str)
+            .unwrap_or("target/report.html");
+        return write_report(&cache, path, use_cache);
+    }
+
+    let (command, day, part) = match &args[..] {
+        [command, day, part, ..] => match (day.parse::<u8>(), part.parse::<u8>()) {
+            (Ok(day), Ok(part)) => (command.as_str(), day, part),
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::FAILURE;
+            }
+        },
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if command == "history" {
+        return history(&cache, day, part);
+    }
+
+    let path = args.get(3).cloned().unwrap_or_else(|| input_path(day));
+    let input = match std::fs::read_to_string(&path) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("could not read {}: {}", path, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match command {
+        "run" => run(&cache, day, part, &input, use_cache),
+        "compare" => compare(day, part, &input),
+        "profile" => profile(day, part, &input),
+        "step" => step(day, part, &input),
+        _ => {
+            eprintln!("{}", USAGE);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// by default each day reads the input.txt sitting next to its crate
+fn input_path(day: u8) -> String {
+    format!("day{:02}/input.txt", day)
+}
+
+fn write_report(cache: &Cache, path: &str, use_cache: bool) -> ExitCode {
+    let reports = report::build(cache, use_cache, |day| {
+        std::fs::read_to_string(input_path(day))
+    });
+    match std::fs::write(path, report::to_html(&reports)) {
+        Ok(()) => {
+            println!("Wrote {}", path);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("could not write {}: {}", path, e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cache: &Cache, day: u8, part: u8, input: &str, use_cache: bool) -> ExitCode {
+    let outcome = if use_cache {
+        cache.solve(day, part, input)
+    } else {
+        cache.refresh(day, part, input).map(Outcome::Solved)
+    };
+
+    match outcome {
+        Ok(Outcome::Cached(answer)) => {
+            println!("Day {} part {}: {} (cached)", day, part, answer);
+            ExitCode::SUCCESS
+        }
+        Ok(Outcome::Solved(solution)) => {
+            println!("Day {} part {}: {}", day, part, solution.answer);
+            println!(
+                "parsed in {:?}, solved in {:?}",
+                solution.parse_time, solution.solve_time
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn compare(day: u8, part: u8, input: &str) -> ExitCode {
+    let comparison = match runner::compare(day, part, input) {
+        Ok(comparison) => comparison,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!(
+        "Day {} part {} (parsed in {:?})",
+        day, part, comparison.parse_time
+    );
+    let width = comparison
+        .timings
+        .iter()
+        .map(|t| t.name.len())
+        .max()
+        .unwrap_or_default();
+    for (timing, relative) in comparison.timings.iter().zip(comparison.relative_times()) {
+        let answer = match &timing.result {
+            Ok(answer) => answer.to_string(),
+            Err(e) => e.to_string(),
+        };
+        println!(
+            "  {:width$}  {:>12?}  {:>8.2}x  {}",
+            timing.name,
+            timing.time,
+            relative,
+            answer,
+            width = width
+        );
+    }
+
+    if comparison.agrees() {
+        println!("all variants agree");
+        ExitCode::SUCCESS
+    } else {
+        println!("variants disagree!");
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(feature = "alloc-stats")]
+fn profile(day: u8, part: u8, input: &str) -> ExitCode {
+    match runner::alloc::profile(day, part, input) {
+        Ok(profile) => {
+            println!("Day {} part {}: {}", day, part, profile.answer);
+            println!("  parse: {}", profile.parse);
+            println!("  solve: {}", profile.solve);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(not(feature = "alloc-stats"))]
+fn profile(_: u8, _: u8, _: &str) -> ExitCode {
+    eprintln!("counting allocations needs the runner built with `--features alloc-stats`");
+    ExitCode::FAILURE
+}
+
+#[cfg(feature = "tui")]
+fn step(day: u8, part: u8, input: &str) -> ExitCode {
+    match runner::tui::run_day(day, part, input) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(not(feature = "tui"))]
+fn step(_: u8, _: u8, _: &str) -> ExitCode {
+    eprintln!("stepping through a day needs the runner built with `--features tui`");
+    ExitCode::FAILURE
+}
+
+fn history(cache: &Cache, day: u8, part: u8) -> ExitCode {
+    let runs = cache.history(day, part);
+    if runs.is_empty() {
+        println!("Day {} part {} hasn't been timed yet", day, part);
+        return ExitCode::SUCCESS;
+    }
+
+    println!("Day {} part {}", day, part);
+    println!(
+        "  {:10}  {:16}  {:16}  {:>12}  {:>12}",
+        "commit", "solver", "input", "parse ms", "solve ms"
+    );
+    for run in runs {
+        println!(
+            "  {:10}  {:16}  {:16}  {:>12.3}  {:>12.3}",
+            run.commit.as_deref().unwrap_or("unknown"),
+            run.version,
+            run.input_hash,
+            run.parse_time_ms,
+            run.solve_time_ms
+        );
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,460 @@
+use crate::cache::{self, Cache, Outcome, Run};
+use crate::days::DAYS;
+use crate::{Answer, Model, SolveError, model, parse};
+use std::fmt::Write;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+pub struct Render {
+    pub title: String,
+    pub svg: String,
+}
+
+pub struct PartReport {
+    pub part: u8,
+    pub result: Result<Outcome, SolveError>,
+    pub history: Vec<Run>,
+}
+
+pub struct DayReport {
+    pub day: u8,
+    pub input: Result<(), String>,
+    pub parts: Vec<PartReport>,
+    pub renders: Vec<Render>,
+}
+
+// solves every part of every day (through the cache, unless told otherwise), then
+// draws whatever pictures the days have to offer
+pub fn build(
+    cache: &Cache,
+    use_cache: bool,
+    read_input: impl Fn(u8) -> std::io::Result<String>,
+) -> Vec<DayReport> {
+    let mut reports = Vec::new();
+    for day in DAYS {
+        let input = match read_input(day.day) {
+            Ok(input) => input,
+            Err(e) => {
+                reports.push(DayReport {
+                    day: day.day,
+                    input: Err(e.to_string()),
+                    parts: Vec::new(),
+                    renders: Vec::new(),
+                });
+                continue;
+            }
+        };
+
+        let parts = day
+            .parts
+            .iter()
+            .map(|part| PartReport {
+                part: part.part,
+                result: if use_cache {
+                    cache.solve(day.day, part.part, &input)
+                } else {
+                    cache
+                        .refresh(day.day, part.part, &input)
+                        .map(Outcome::Solved)
+                },
+                history: cache.history(day.day, part.part),
+            })
+            .collect::<Vec<_>>();
+
+        // a picture that fails to draw shouldn't take the rest of the report with it
+        let renders = match parse(day, &input) {
+            Ok(parsed) => catch_unwind(AssertUnwindSafe(|| renders(day.day, &parsed, &parts)))
+                .unwrap_or_default(),
+            Err(_) => Vec::new(),
+        };
+
+        reports.push(DayReport {
+            day: day.day,
+            input: Ok(()),
+            parts,
+            renders,
+        });
+    }
+
+    reports
+}
+
+fn renders(day: u8, parsed: &Model, parts: &[PartReport]) -> Vec<Render> {
+    match day {
+        12 => vec![render_regions(model(parsed))],
+        14 => {
+            // the picture is wherever part 2 found it
+            let Some(Ok(outcome)) = parts.iter().find(|p| p.part == 2).map(|p| &p.result) else {
+                return Vec::new();
+            };
+            let Answer::Number(steps) = outcome.answer() else {
+                return Vec::new();
+            };
+            vec![render_robots(
+                model::<Vec<day14::Robot>>(parsed),
+                *steps as i64,
+            )]
+        }
+        16 => vec![render_best_paths(model(parsed))],
+        23 => vec![render_largest_clique(model(parsed))],
+        _ => Vec::new(),
+    }
+}
+
+fn render_regions(garden: &day12::Garden) -> Render {
+    // regions come out in hash order, so sort them to keep the colours stable between runs
+    let mut regions = garden
+        .to_regions()
+        .iter()
+        .map(|r| {
+            let mut plots = r.plots().map(<(i64, i64)>::from).collect::<Vec<_>>();
+            plots.sort_by_key(|&(x, y)| (y, x));
+            plots
+        })
+        .collect::<Vec<_>>();
+    regions.sort();
+
+    let cells = regions
+        .iter()
+        .enumerate()
+        .flat_map(|(i, plots)| plots.iter().map(move |&plot| (plot, colour(i))))
+        .collect::<Vec<_>>();
+    let width = cells.iter().map(|c| c.0.0).max().unwrap_or_default() + 1;
+    let height = cells.iter().map(|c| c.0.1).max().unwrap_or_default() + 1;
+    Render {
+        title: format!("Garden regions ({} of them)", regions.len()),
+        svg: grid_svg(width, height, cells),
+    }
+}
+
+fn render_robots(robots: &[day14::Robot], steps: i64) -> Render {
+    let room = day14::room_after(robots, steps, (101, 103));
+    let (width, height) = room.room_dimensions();
+    let cells = room.robots().map(|r| (r, "#2a7a2a".to_string())).collect();
+    Render {
+        title: format!("Robots after {} seconds", steps),
+        svg: grid_svg(width, height, cells),
+    }
+}
+
+fn render_best_paths(maze: &day16::Input) -> Render {
+    let spaces = maze
+        .spaces()
+        .map(<(i64, i64)>::from)
+        .collect::<std::collections::HashSet<_>>();
+    let path = day16::best_path_tiles(maze)
+        .into_iter()
+        .map(<(i64, i64)>::from)
+        .collect::<std::collections::HashSet<_>>();
+    let width = spaces.iter().map(|s| s.0).max().unwrap_or_default() + 2;
+    let height = spaces.iter().map(|s| s.1).max().unwrap_or_default() + 2;
+
+    let mut cells = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            let colour = if path.contains(&(x, y)) {
+                "#e8b500"
+            } else if spaces.contains(&(x, y)) {
+                "#fff"
+            } else {
+                "#333"
+            };
+            cells.push(((x, y), colour.to_string()));
+        }
+    }
+
+    Render {
+        title: format!("Tiles on a best path ({})", path.len()),
+        svg: grid_svg(width, height, cells),
+    }
+}
+
+fn render_largest_clique(graph: &day23::Graph) -> Render {
+    let cliques = day23::find_maximal_cliques(graph);
+    let clique = cliques.last().cloned().unwrap_or_default();
+
+    // everyone sat around a table, with a line between each pair that are connected
+    let radius = 150.0;
+    let centre = 200.0;
+    let points = (0..clique.len())
+        .map(|i| {
+            let angle = std::f64::consts::TAU * i as f64 / clique.len() as f64;
+            (centre + radius * angle.sin(), centre - radius * angle.cos())
+        })
+        .collect::<Vec<_>>();
+
+    let mut svg = String::from(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 400 400" width="400" height="400">"#,
+    );
+    svg.push('\n');
+    for i in 0..clique.len() {
+        for j in i + 1..clique.len() {
+            if graph.has_edge(clique[i], clique[j]) {
+                let _ = writeln!(
+                    svg,
+                    r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#9ab" stroke-width="0.5"/>"##,
+                    points[i].0, points[i].1, points[j].0, points[j].1
+                );
+            }
+        }
+    }
+    for (name, (x, y)) in clique.iter().zip(&points) {
+        let _ = writeln!(
+            svg,
+            r##"<circle cx="{:.1}" cy="{:.1}" r="14" fill="#1f5f8b"/><text x="{:.1}" y="{:.1}" fill="#fff" font-size="11" text-anchor="middle" dominant-baseline="central">{}</text>"##,
+            x,
+            y,
+            x,
+            y,
+            escape(name)
+        );
+    }
+    svg.push_str("</svg>");
+
+    Render {
+        title: format!("The largest clique ({} computers)", clique.len()),
+        svg,
+    }
+}
+
+// spread the hues out so neighbouring indices don't end up looking alike
+fn colour(index: usize) -> String {
+    format!("hsl({}, 65%, 60%)", (index * 137) % 360)
+}
+
+// one square per cell, rows top to bottom so the output diffs nicely
+fn grid_svg(width: i64, height: i64, mut cells: Vec<((i64, i64), String)>) -> String {
+    cells.sort_by_key(|&((x, y), _)| (y, x));
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {} {}" width="{}" height="{}" shape-rendering="crispEdges" style="background: #fff">"#,
+        width,
+        height,
+        width * 4,
+        height * 4
+    );
+    svg.push('\n');
+    for ((x, y), colour) in cells {
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="1" height="1" fill="{}"/>"#,
+            x, y, colour
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+// solve times range from microseconds to minutes, so the bars are on a log scale
+fn time_bar(ms: f64) -> String {
+    let width = ((ms.max(0.001).log10() + 3.0) * 25.0).clamp(1.0, 200.0);
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="12"><rect width="{:.1}" height="12" fill="#4a90d9"/></svg>"##,
+        width
+    )
+}
+
+fn history_chart(history: &[Run]) -> String {
+    if history.len() < 2 {
+        return String::new();
+    }
+
+    let slowest = history
+        .iter()
+        .map(|r| r.solve_time_ms)
+        .fold(f64::MIN_POSITIVE, f64::max);
+    let step = 200.0 / (history.len() - 1) as f64;
+    let points = history
+        .iter()
+        .enumerate()
+        .map(|(i, r)| {
+            format!(
+                "{:.1},{:.1}",
+                i as f64 * step,
+                24.0 - 22.0 * r.solve_time_ms / slowest
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let commits = history
+        .iter()
+        .map(|r| r.commit.as_deref().unwrap_or("unknown"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="26"><title>{}</title><polyline points="{}" fill="none" stroke="#d9534f"/></svg>"##,
+        escape(&commits),
+        points
+    )
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn to_html(reports: &[DayReport]) -> String {
+    let mut html = String::new();
+    let commit = cache::current_commit().unwrap_or_else(|| "unknown".to_string());
+    let _ = writeln!(
+        html,
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Advent of Code 2024 - {}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; }}
+td, th {{ padding: 0.2em 0.8em; text-align: left; border-bottom: 1px solid #ddd; }}
+.error {{ color: #c00; }}
+.cached {{ color: #888; }}
+figure {{ display: inline-block; margin: 1em; vertical-align: top; }}
+</style>
+</head>
+<body>
+<h1>Advent of Code 2024</h1>
+<p>Commit {}</p>"#,
+        escape(&commit),
+        escape(&commit)
+    );
+
+    html.push_str("<h2>Answers</h2>\n<table>\n");
+    html.push_str("<tr><th>Day</th><th>Part</th><th>Answer</th><th>Solve time</th><th></th><th>History</th></tr>\n");
+    for report in reports {
+        if let Err(e) = &report.input {
+            let _ = writeln!(
+                html,
+                r#"<tr><td>{}</td><td></td><td class="error">no input: {}</td><td></td><td></td><td></td></tr>"#,
+                report.day,
+                escape(e)
+            );
+            continue;
+        }
+
+        for part in &report.parts {
+            // cached answers weren't timed this time round, so fall back on the last time they were
+            let time = match &part.result {
+                Ok(Outcome::Solved(solution)) => Some(solution.solve_time.as_secs_f64() * 1000.0),
+                _ => part.history.last().map(|r| r.solve_time_ms),
+            };
+            let answer = match &part.result {
+                Ok(Outcome::Solved(solution)) => escape(&solution.answer.to_string()),
+                Ok(Outcome::Cached(answer)) => {
+                    format!(
+                        r#"{} <span class="cached">(cached)</span>"#,
+                        escape(&answer.to_string())
+                    )
+                }
+                Err(e) => format!(r#"<span class="error">{}</span>"#, escape(&e.to_string())),
+            };
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                report.day,
+                part.part,
+                answer,
+                time.map(|ms| format!("{:.3} ms", ms)).unwrap_or_default(),
+                time.map(time_bar).unwrap_or_default(),
+                history_chart(&part.history)
+            );
+        }
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>Pictures</h2>\n");
+    for report in reports {
+        for render in &report.renders {
+            let _ = writeln!(
+                html,
+                "<figure>\n<figcaption>Day {}: {}</figcaption>\n{}\n</figure>",
+                report.day,
+                escape(&render.title),
+                render.svg
+            );
+        }
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+#[test]
+pub fn test_report() {
+    let dir = std::env::temp_dir().join(format!("runner-report-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let cache = Cache::new(&dir);
+
+    let inputs = [
+        (
+            12,
+            "AAAA
+BBCD
+BBCC
+EEEC
+",
+        ),
+        (
+            16,
+            "###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############
+",
+        ),
+        (
+            23,
+            "ka-co
+ta-co
+de-co
+ta-ka
+de-ta
+ka-de
+",
+        ),
+    ];
+    let reports = build(&cache, true, |day| {
+        inputs
+            .iter()
+            .find(|(d, _)| *d == day)
+            .map(|(_, input)| input.to_string())
+            .ok_or(std::io::ErrorKind::NotFound.into())
+    });
+    assert_eq!(reports.len(), 25);
+
+    let day12 = &reports[11];
+    assert!(day12.input.is_ok());
+    assert_eq!(
+        day12.parts[0].result.as_ref().unwrap().answer(),
+        &Answer::Number(140)
+    );
+    assert_eq!(day12.renders[0].title, "Garden regions (5 of them)");
+    assert_eq!(day12.renders[0].svg.matches("<rect").count(), 16);
+
+    assert_eq!(reports[15].renders[0].title, "Tiles on a best path (45)");
+    assert_eq!(
+        reports[22].renders[0].title,
+        "The largest clique (4 computers)"
+    );
+    assert_eq!(reports[22].renders[0].svg.matches("<line").count(), 6);
+    assert!(reports[0].input.is_err());
+
+    let html = to_html(&reports);
+    assert!(html.contains("Garden regions"));
+    assert!(html.contains("co,de,ka,ta"));
+    assert!(html.contains("no input"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,125 @@
+use crate::{SolveError, solve};
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+// routes a request to the matching solver - the body is the raw puzzle input
+pub fn respond(method: &Method, url: &str, body: &str) -> (u16, Value) {
+    let segments = url.trim_matches('/').split('/').collect::<Vec<_>>();
+    let (day, part) = match segments[..] {
+        ["day", day, "part", part] => match (day.parse::<u8>(), part.parse::<u8>()) {
+            (Ok(day), Ok(part)) => (day, part),
+            _ => return (404, json!({ "error": format!("no route for {}", url) })),
+        },
+        _ => return (404, json!({ "error": format!("no route for {}", url) })),
+    };
+
+    if method != &Method::Post {
+        return (405, json!({ "error": "post the puzzle input to this url" }));
+    }
+
+    match solve(day, part, body) {
+        Ok(solution) => (
+            200,
+            json!({
+                "day": day,
+                "part": part,
+                "answer": solution.answer,
+                "parse_time_ms": solution.parse_time.as_secs_f64() * 1000.0,
+                "solve_time_ms": solution.solve_time.as_secs_f64() * 1000.0,
+            }),
+        ),
+        Err(error) => {
+            let status = match error {
+                SolveError::UnknownPuzzle { .. } => 404,
+                SolveError::Parse(_) => 400,
+                SolveError::Panicked(_) => 500,
+                SolveError::TimedOut(_) => 504,
+            };
+            (status, json!({ "error": error.to_string() }))
+        }
+    }
+}
+
+pub fn handle(mut request: Request) -> std::io::Result<()> {
+    let mut body = String::new();
+    let (status, value) = match request.as_reader().read_to_string(&mut body) {
+        Ok(_) => respond(request.method(), request.url(), &body),
+        Err(_) => (400, json!({ "error": "the puzzle input must be utf-8" })),
+    };
+
+    let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+    request.respond(
+        Response::from_string(value.to_string())
+            .with_status_code(status)
+            .with_header(header),
+    )
+}
+
+// each request gets its own thread so one slow solver doesn't hold up the rest
+pub fn serve(server: Server) {
+    for request in server.incoming_requests() {
+        std::thread::spawn(move || {
+            if let Err(e) = handle(request) {
+                eprintln!("failed to respond: {}", e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+fn post(address: std::net::SocketAddr, url: &str, body: &str) -> (u16, Value) {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+        url,
+        body.len(),
+        body
+    )
+    .unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head
+        .split_ascii_whitespace()
+        .nth(1)
+        .unwrap()
+        .parse()
+        .unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[test]
+pub fn test_localhost() {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let address = server.server_addr().to_ip().unwrap();
+    std::thread::spawn(move || serve(server));
+
+    let input = "7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9
+";
+    let (status, value) = post(address, "/day/2/part/2", input);
+    assert_eq!(status, 200);
+    assert_eq!(value["answer"], 4);
+
+    let (status, _) = post(address, "/day/5/part/1", "nonsense");
+    assert_eq!(status, 400);
+
+    let (status, _) = post(address, "/day/26/part/1", input);
+    assert_eq!(status, 404);
+}
+
+#[test]
+pub fn test_routes() {
+    assert_eq!(respond(&Method::Get, "/day/1/part/1", "").0, 405);
+    assert_eq!(respond(&Method::Post, "/day/one/part/1", "").0, 404);
+    assert_eq!(respond(&Method::Post, "/", "").0, 404);
+}
@@ -0,0 +1,643 @@
+use ratatui::Frame;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+// anything that can be watched one step at a time
+pub trait Simulation: Sized + Clone {
+    fn title(&self) -> String;
+    // None once there's nothing left to do
+    fn step(&self) -> Option<Self>;
+    // straight to `steps` steps later, for the simulations that can get there without
+    // going through every step in between
+    fn leap(&self, _steps: usize) -> Option<Self> {
+        None
+    }
+    fn view(&self) -> String;
+    // the side panel
+    fn stats(&self) -> Vec<(&'static str, String)>;
+}
+
+// day 15 - the robot working through its list of moves
+#[derive(Clone)]
+pub struct Warehouse {
+    state: day15::State,
+    moves: Arc<[day15::Direction]>,
+    next: usize,
+    wide: bool,
+}
+
+impl Warehouse {
+    pub fn new(input: &day15::Input, wide: bool) -> Self {
+        let input = if wide {
+            input.expanded()
+        } else {
+            input.clone()
+        };
+        Self {
+            state: input.initial_state().clone(),
+            moves: input.moves().into(),
+            next: 0,
+            wide,
+        }
+    }
+}
+
+impl Simulation for Warehouse {
+    fn title(&self) -> String {
+        format!("Day 15{}", if self.wide { " (wide)" } else { "" })
+    }
+
+    fn step(&self) -> Option<Self> {
+        let direction = *self.moves.get(self.next)?;
+        let state = if self.wide {
+            self.state.step_expanded(direction)
+        } else {
+            self.state.step(direction)
+        };
+        Some(Self {
+            state,
+            next: self.next + 1,
+            ..self.clone()
+        })
+    }
+
+    fn view(&self) -> String {
+        self.state.render(self.wide)
+    }
+
+    fn stats(&self) -> Vec<(&'static str, String)> {
+        let robot: (i64, i64) = self.state.robot().into();
+        vec![
+            ("robot", format!("{},{}", robot.0, robot.1)),
+            (
+                "next move",
+                self.moves
+                    .get(self.next)
+                    .map_or("none".to_string(), |m| format!("{:?}", m)),
+            ),
+            ("moves left", (self.moves.len() - self.next).to_string()),
+            ("GPS sum", self.state.gps_sum().to_string()),
+        ]
+    }
+}
+
+// day 6 - the guard's walk, a cell or a turn at a time
+#[derive(Clone)]
+pub struct Patrol {
+    input: day06::Input,
+    step: usize,
+    // the step each cell was first reached on, shared by every state of the walk so that a
+    // step only has its own cell to add
+    first_visits: Rc<RefCell<HashMap<day06::Coord, usize>>>,
+}
+
+impl Patrol {
+    pub fn new(input: &day06::Input) -> Self {
+        Self {
+            input: input.clone(),
+            step: 0,
+            first_visits: Rc::new(RefCell::new(HashMap::from([(input.guard().0, 0)]))),
+        }
+    }
+
+    fn visited(&self) -> usize {
+        self.first_visits
+            .borrow()
+            .values()
+            .filter(|&&step| step <= self.step)
+            .count()
+    }
+}
+
+impl Simulation for Patrol {
+    fn title(&self) -> String {
+        "Day 6".to_string()
+    }
+
+    fn step(&self) -> Option<Self> {
+        let input = self.input.step()?;
+        let step = self.step + 1;
+        self.first_visits
+            .borrow_mut()
+            .entry(input.guard().0)
+            .or_insert(step);
+        Some(Self {
+            input,
+            step,
+            first_visits: self.first_visits.clone(),
+        })
+    }
+
+    fn view(&self) -> String {
+        self.input.to_string()
+    }
+
+    fn stats(&self) -> Vec<(&'static str, String)> {
+        let (location, orientation) = self.input.guard();
+        let (x, y): (usize, usize) = location.into();
+        vec![
+            ("guard", format!("{},{}", x, y)),
+            ("facing", format!("{:?}", orientation)),
+            ("cells visited", self.visited().to_string()),
+        ]
+    }
+}
+
+// day 14 - the robots never stop, so this one can be stepped forever
+#[derive(Clone)]
+pub struct Robots {
+    // where they started, since any later room can be worked out from there directly
+    robots: Arc<[day14::Robot]>,
+    elapsed: i64,
+    room_dimensions: (i64, i64),
+}
+
+impl Robots {
+    pub fn new(robots: &[day14::Robot], room_dimensions: (i64, i64)) -> Self {
+        Self {
+            robots: robots.into(),
+            elapsed: 0,
+            room_dimensions,
+        }
+    }
+
+    fn room(&self) -> day14::Room {
+        day14::room_after(&self.robots, self.elapsed, self.room_dimensions)
+    }
+}
+
+impl Simulation for Robots {
+    fn title(&self) -> String {
+        "Day 14".to_string()
+    }
+
+    fn step(&self) -> Option<Self> {
+        self.leap(1)
+    }
+
+    fn leap(&self, steps: usize) -> Option<Self> {
+        Some(Self {
+            elapsed: self.elapsed + steps as i64,
+            ..self.clone()
+        })
+    }
+
+    fn view(&self) -> String {
+        self.room().to_string()
+    }
+
+    fn stats(&self) -> Vec<(&'static str, String)> {
+        let quadrants = self.room().quadrant_counts();
+        vec![
+            ("upper left", quadrants[0].to_string()),
+            ("upper right", quadrants[1].to_string()),
+            ("lower left", quadrants[2].to_string()),
+            ("lower right", quadrants[3].to_string()),
+            (
+                "safety factor",
+                quadrants.iter().product::<i64>().to_string(),
+            ),
+        ]
+    }
+}
+
+// day 17 - one instruction at a time
+#[derive(Clone)]
+pub struct Machine(pub day17::Computer);
+
+impl Simulation for Machine {
+    fn title(&self) -> String {
+        "Day 17".to_string()
+    }
+
+    fn step(&self) -> Option<Self> {
+        // a reserved operand stops the machine just like running off the end does
+        self.0.try_step().ok().flatten().map(Machine)
+    }
+
+    fn view(&self) -> String {
+        let program = self.0.program();
+        let pointer = self.0.instruction_pointer();
+        let instructions = program
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let output = self
+            .0
+            .output()
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "{}\n{}^\n\noutput: {}\n",
+            instructions,
+            " ".repeat(pointer * 2),
+            output
+        )
+    }
+
+    fn stats(&self) -> Vec<(&'static str, String)> {
+        let (a, b, c) = self.0.registers();
+        vec![
+            ("A", a.to_string()),
+            ("B", b.to_string()),
+            ("C", c.to_string()),
+            ("pointer", self.0.instruction_pointer().to_string()),
+        ]
+    }
+}
+
+const HELP: &str = "←/→ step  space play  g<n>⏎ jump  q quit";
+
+// how many of the latest steps are kept; stepping back past them replays from the start
+const HISTORY: usize = 1000;
+
+pub struct Stepper<S> {
+    start: S,
+    // the most recent states, the first of which is step `first`
+    history: VecDeque<S>,
+    first: usize,
+    current: usize,
+    // the last step there is, once it's been reached
+    last: Option<usize>,
+    playing: bool,
+    // the digits typed so far after pressing g
+    jump: Option<String>,
+    quit: bool,
+}
+
+impl<S: Simulation> Stepper<S> {
+    pub fn new(start: S) -> Self {
+        Self {
+            history: VecDeque::from([start.clone()]),
+            start,
+            first: 0,
+            current: 0,
+            last: None,
+            playing: false,
+            jump: None,
+            quit: false,
+        }
+    }
+
+    pub fn step(&self) -> usize {
+        self.current
+    }
+
+    pub fn state(&self) -> &S {
+        &self.history[self.current - self.first]
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn has_quit(&self) -> bool {
+        self.quit
+    }
+
+    fn newest(&self) -> usize {
+        self.first + self.history.len() - 1
+    }
+
+    // nothing but `state` is kept, as step `step`
+    fn restart_at(&mut self, step: usize, state: S) {
+        self.history = VecDeque::from([state]);
+        self.first = step;
+        self.current = step;
+    }
+
+    // false if the simulation has already finished
+    pub fn forward(&mut self) -> bool {
+        if self.current == self.newest() {
+            if self.last == Some(self.current) {
+                return false;
+            }
+            match self.state().step() {
+                Some(next) => {
+                    self.history.push_back(next);
+                    if self.history.len() > HISTORY {
+                        self.history.pop_front();
+                        self.first += 1;
+                    }
+                }
+                None => {
+                    self.last = Some(self.current);
+                    return false;
+                }
+            }
+        }
+        self.current += 1;
+        true
+    }
+
+    pub fn back(&mut self) {
+        if self.current > self.first {
+            self.current -= 1;
+        } else if self.current > 0 {
+            self.jump_to(self.current - 1);
+        }
+    }
+
+    // as close to step n as the simulation goes
+    pub fn jump_to(&mut self, n: usize) {
+        if n < self.first {
+            self.restart_at(0, self.start.clone());
+        }
+        if n <= self.newest() {
+            self.current = n;
+            return;
+        }
+        self.current = self.newest();
+        if let Some(state) = self.state().leap(n - self.current) {
+            self.restart_at(n, state);
+            return;
+        }
+        while self.current < n && self.forward() {}
+    }
+
+    // called on every tick of the clock while playing
+    pub fn tick(&mut self) {
+        if self.playing && !self.forward() {
+            self.playing = false;
+        }
+    }
+
+    pub fn press(&mut self, key: KeyCode) {
+        if let Some(digits) = &mut self.jump {
+            match key {
+                KeyCode::Char(c) if c.is_ascii_digit() => digits.push(c),
+                KeyCode::Backspace => {
+                    digits.pop();
+                }
+                KeyCode::Enter => {
+                    if let Ok(n) = digits.parse() {
+                        self.jump_to(n);
+                    }
+                    self.jump = None;
+                }
+                _ => self.jump = None,
+            }
+            return;
+        }
+
+        match key {
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.playing = false;
+                self.forward();
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.playing = false;
+                self.back();
+            }
+            KeyCode::Home => self.jump_to(0),
+            KeyCode::Char(' ') => self.playing = !self.playing,
+            KeyCode::Char('g') => {
+                self.playing = false;
+                self.jump = Some(String::new());
+            }
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            _ => {}
+        }
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [view, panel] =
+            Layout::horizontal([Constraint::Min(0), Constraint::Length(28)]).areas(main);
+
+        let state = self.state();
+        let title = format!(
+            " {} - step {}{} ",
+            state.title(),
+            self.current,
+            if self.last == Some(self.current) {
+                " (done)"
+            } else {
+                ""
+            }
+        );
+        frame.render_widget(
+            Paragraph::new(state.view()).block(Block::bordered().title(title)),
+            view,
+        );
+
+        let stats = state
+            .stats()
+            .into_iter()
+            .map(|(name, value)| Line::from(format!("{}: {}", name, value)))
+            .collect::<Vec<_>>();
+        frame.render_widget(
+            Paragraph::new(stats).block(Block::bordered().title(" state ")),
+            panel,
+        );
+
+        let footer_text = match &self.jump {
+            Some(digits) => format!("jump to step: {}", digits),
+            None if self.playing => format!("playing  {}", HELP),
+            None => HELP.to_string(),
+        };
+        frame.render_widget(Paragraph::new(footer_text), footer);
+    }
+}
+
+const PLAY_SPEED: Duration = Duration::from_millis(100);
+
+pub fn run<S: Simulation>(start: S) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let mut stepper = Stepper::new(start);
+    let result = (|| {
+        while !stepper.has_quit() {
+            terminal.draw(|frame| stepper.draw(frame))?;
+            // while playing, no key press before the timeout means it's time for the next step
+            if stepper.is_playing() && !event::poll(PLAY_SPEED)? {
+                stepper.tick();
+                continue;
+            }
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                stepper.press(key.code);
+            }
+        }
+        Ok(())
+    })();
+    ratatui::restore();
+    result
+}
+
+// the same room size the registry solves day 14 with
+const ROOM_DIMENSIONS: (i64, i64) = (101, 103);
+
+pub fn run_day(day: u8, part: u8, input: &str) -> io::Result<()> {
+    let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidInput, e);
+    match day {
+        6 => run(Patrol::new(
+            &day06::try_parse_input(input).map_err(|e| invalid(e.to_string()))?,
+        )),
+        14 => run(Robots::new(
+            &day14::try_parse_input(input).map_err(|e| invalid(e.to_string()))?,
+            ROOM_DIMENSIONS,
+        )),
+        15 => run(Warehouse::new(
+            &day15::try_parse_input(input).map_err(|e| invalid(e.to_string()))?,
+            part == 2,
+        )),
+        17 => run(Machine(
+            day17::try_parse_input(input.trim()).map_err(|e| invalid(e.to_string()))?,
+        )),
+        _ => Err(invalid(format!(
+            "there's nothing to step through for day {}",
+            day
+        ))),
+    }
+}
+
+#[cfg(test)]
+fn render_screen(stepper: &Stepper<impl Simulation>) -> String {
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 14)).unwrap();
+    terminal.draw(|frame| stepper.draw(frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    buffer
+        .content()
+        .chunks(buffer.area.width as usize)
+        .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+pub fn test_warehouse() {
+    let input = day15::parse_input(
+        "#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######
+
+<vv<<^^<<^^",
+    );
+    let mut stepper = Stepper::new(Warehouse::new(&input, true));
+    for key in [KeyCode::Right, KeyCode::Right, KeyCode::Left] {
+        stepper.press(key);
+    }
+    assert_eq!(stepper.step(), 1);
+    let screen = render_screen(&stepper);
+    assert!(screen.contains("Day 15 (wide) - step 1"));
+    assert!(screen.contains("##...[][]@..##"));
+    assert!(screen.contains("moves left: 10"));
+
+    // past the end stops at the last move
+    for key in "g99".chars().map(KeyCode::Char).chain([KeyCode::Enter]) {
+        stepper.press(key);
+    }
+    assert_eq!(stepper.step(), 11);
+    let screen = render_screen(&stepper);
+    assert!(screen.contains("step 11 (done)"));
+    assert!(screen.contains("GPS sum: 618"));
+
+    stepper.press(KeyCode::Home);
+    assert_eq!(stepper.step(), 0);
+}
+
+#[test]
+pub fn test_play() {
+    let input = day06::parse_input(
+        ".#.
+...
+.^.
+",
+    );
+    let mut stepper = Stepper::new(Patrol::new(&input));
+    stepper.press(KeyCode::Char(' '));
+    assert!(render_screen(&stepper).contains("playing"));
+    for _ in 0..10 {
+        stepper.tick();
+    }
+    // up, turn, right, and then off the map - which stops the playing
+    assert_eq!(stepper.step(), 3);
+    assert!(!stepper.is_playing());
+    assert!(render_screen(&stepper).contains("cells visited: 3"));
+
+    stepper.press(KeyCode::Char('q'));
+    assert!(stepper.has_quit());
+}
+
+#[test]
+pub fn test_robots_and_machine() {
+    let robots = day14::parse_input(
+        "p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3
+",
+    );
+    let mut stepper = Stepper::new(Robots::new(&robots, (11, 7)));
+    stepper.jump_to(100);
+    let screen = render_screen(&stepper);
+    assert!(screen.contains("......#..#."));
+    assert!(screen.contains("safety factor: 12"));
+
+    let computer = day17::parse_input(
+        "Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0",
+    );
+    let mut stepper = Stepper::new(Machine(computer));
+    stepper.press(KeyCode::Right);
+    let screen = render_screen(&stepper);
+    assert!(screen.contains("A: 364"));
+    assert!(screen.contains("pointer: 2"));
+}
+
+#[test]
+pub fn test_history() {
+    // a long way to walk straight up, so the earliest steps drop out of the history
+    let input = day06::parse_input(&format!("{}.\n^\n", ".\n".repeat(1499)));
+    let mut stepper = Stepper::new(Patrol::new(&input));
+    stepper.jump_to(1400);
+    assert!(render_screen(&stepper).contains("cells visited: 1401"));
+
+    // further back than is kept gets replayed
+    stepper.jump_to(300);
+    assert_eq!(stepper.step(), 300);
+    assert!(render_screen(&stepper).contains("guard: 0,1200"));
+    assert!(render_screen(&stepper).contains("cells visited: 301"));
+    stepper.jump_to(2000);
+    assert_eq!(stepper.step(), 1500);
+    assert!(render_screen(&stepper).contains("(done)"));
+
+    // the robots go straight there, and back from there
+    let robots = day14::parse_input("p=2,4 v=2,-3\n");
+    let mut stepper = Stepper::new(Robots::new(&robots, (11, 7)));
+    stepper.jump_to(1_000_000);
+    stepper.back();
+    assert_eq!(stepper.step(), 999_999);
+    assert!(!render_screen(&stepper).contains("(done)"));
+}